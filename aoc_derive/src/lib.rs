@@ -151,3 +151,80 @@ pub fn hash_map_from_str(item: TokenStream) -> TokenStream {
     }
     .into()
 }
+
+fn find_char_attr(attrs: &[syn::Attribute], name: &str) -> Option<char> {
+    attrs.iter().find_map(|attr| {
+        let nv = attr.meta.require_name_value().ok()?;
+        if !nv.path.is_ident(name) {
+            return None;
+        }
+        match &nv.value {
+            Expr::Lit(ExprLit { lit: Lit::Char(lit_char), .. }) => Some(lit_char.value()),
+            _ => panic!("Expected a char literal for #[{name} = ...]"),
+        }
+    })
+}
+
+/// Maps each unit variant of an enum to the char given by its `#[cell = '.']` attribute.
+/// Chars listed in `#[markers = "SE"]` on the enum itself are accepted by Input::grid_of as
+/// positions that are replaced by the default cell.
+#[proc_macro_derive(GridCell, attributes(cell, markers))]
+pub fn grid_cell(item: TokenStream) -> TokenStream {
+    let s = parse_macro_input!(item as syn::DeriveInput);
+    let ident = s.ident;
+
+    let syn::Data::Enum(data) = s.data else {
+        panic!("GridCell can only be derived for enums");
+    };
+
+    let markers = s
+        .attrs
+        .iter()
+        .find_map(|attr| {
+            let nv = attr.meta.require_name_value().ok()?;
+            if !nv.path.is_ident("markers") {
+                return None;
+            }
+            match &nv.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(lit_str), .. }) => Some(lit_str.value()),
+                _ => panic!("Expected a string literal for #[markers = ...]"),
+            }
+        })
+        .unwrap_or_default()
+        .chars()
+        .collect::<Vec<_>>();
+
+    let (variants, chars): (Vec<_>, Vec<_>) = data
+        .variants
+        .iter()
+        .map(|variant| {
+            assert!(
+                matches!(variant.fields, syn::Fields::Unit),
+                "GridCell variants must not have fields"
+            );
+            let c = find_char_attr(&variant.attrs, "cell")
+                .unwrap_or_else(|| panic!("Missing #[cell = ...] for variant {}", variant.ident));
+            (variant.ident.clone(), c)
+        })
+        .unzip();
+
+    quote! {
+        impl utils::grid::GridCell for #ident {
+            const MARKERS: &'static [char] = &[#(#markers),*];
+
+            fn from_char(c: char) -> Option<Self> {
+                match c {
+                    #(#chars => Some(Self::#variants),)*
+                    _ => None,
+                }
+            }
+
+            fn to_char(&self) -> char {
+                match self {
+                    #(Self::#variants => #chars,)*
+                }
+            }
+        }
+    }
+    .into()
+}
@@ -1,20 +1,19 @@
 use aoc_derive::aoc_main;
-use itertools::{FoldWhile, Itertools};
-use num::Integer;
-use utils::math::{Box2D, Vec2D};
+use utils::math::{Polygon, Vec2D};
 use utils::sparse_grid::SparseGrid;
 use utils::*;
 
+/// Returns the positions of the loop in the order they are visited
 fn try_find_loop(
     start: Vec2D,
     start_heading: Vec2D,
     grid: &SparseGrid<char>,
-) -> Option<SparseGrid<char>> {
+) -> Option<Vec<Vec2D>> {
     let mut pos = start;
     let mut heading = start_heading;
-    let mut main_loop = SparseGrid::new();
+    let mut main_loop = Vec::new();
     loop {
-        main_loop.insert(pos, *grid.get(pos).unwrap());
+        main_loop.push(pos);
         pos += heading;
 
         if pos == start {
@@ -42,47 +41,21 @@ fn try_find_loop(
     }
 }
 
-fn is_inside_loop(point: Vec2D, bounds: &Box2D, main_loop: &SparseGrid<char>) -> bool {
-    if main_loop.contains_key(&point) {
-        return false;
-    }
-
-    (point.y..)
-        .fold_while(0, |num_crossings, y| {
-            if y > bounds.upper().y {
-                return FoldWhile::Done(num_crossings);
-            }
-
-            FoldWhile::Continue(
-                num_crossings
-                    + match main_loop.get((point.x, y)) {
-                        None => 0,
-                        Some('7' | '|' | 'J') => 0, // no crossing, "squeezing through" the loop
-                        Some(_) => 1,
-                    },
-            )
-        })
-        .into_inner()
-        .is_odd()
-}
-
 #[aoc_main]
 fn solve(input: Input) -> impl Into<Solution> {
     let grid = input.char_sparse_grid();
 
-    let start = grid.iter().find_map(|(&pos, &c)| (c == 'S').then_some(pos)).unwrap();
+    let start = grid.iter().find_map(|(pos, &c)| (c == 'S').then_some(pos)).unwrap();
 
     let main_loop = [(0, 1), (0, -1), (1, 0), (-1, 0)]
         .into_iter()
         .find_map(|pos| try_find_loop(start, pos.into(), &grid))
         .expect("Did not find loop!");
 
-    let loop_bounds: Box2D = main_loop.iter().map(|(pos, _)| *pos).collect();
-
-    let part2 =
-        loop_bounds.points_inside().filter(|&p| is_inside_loop(p, &loop_bounds, &main_loop)).count();
+    let part1 = main_loop.len() / 2;
+    let part2 = Polygon::new(main_loop).interior_points();
 
-    (main_loop.len() / 2, part2)
+    (part1, part2)
 }
 
 #[cfg(test)]
@@ -1,77 +1,59 @@
-use std::collections::HashMap;
-
 use aoc_derive::aoc_main;
 use itertools::Itertools;
 use rayon::prelude::*;
 use utils::*;
 
 fn count_arrangements(springs: &str, numbers: Vec<usize>) -> usize {
-    count_arrangements_impl(&springs.chars().collect_vec(), &numbers, 0, &mut HashMap::new())
+    count_arrangements_impl(&mut Memo::new(), &springs.chars().collect_vec(), &numbers, 0)
 }
 
-#[derive(PartialEq, Eq, Hash)]
-struct CacheKey(Vec<char>, Vec<usize>, usize);
+type Cache<'a> = Memo<(&'a [char], &'a [usize], usize), usize>;
 
-fn count_arrangements_impl(
-    springs: &[char],
-    numbers: &[usize],
+fn count_arrangements_impl<'a>(
+    cache: &mut Cache<'a>,
+    springs: &'a [char],
+    numbers: &'a [usize],
     broken_count: usize,
-    cache: &mut HashMap<CacheKey, usize>,
 ) -> usize {
-    if let Some(result) = cache.get(&CacheKey(springs.to_vec(), numbers.to_vec(), broken_count)) {
-        return *result;
-    }
-
-    let result = match (springs.first(), springs.get(1)) {
-        (Some('.'), Some(_)) => match (numbers, broken_count) {
-            (_, 0) => count_arrangements_impl(&springs[1..], numbers, 0, cache),
-            ([], _) => {
-                if broken_count == 0 {
-                    1
-                } else {
-                    0
-                }
-            }
+    cache.get_or_insert_with((springs, numbers, broken_count), |cache| match springs.first() {
+        Some('?') => {
+            count_with_first(cache, '#', &springs[1..], numbers, broken_count)
+                + count_with_first(cache, '.', &springs[1..], numbers, broken_count)
+        }
+        Some(&first) => count_with_first(cache, first, &springs[1..], numbers, broken_count),
+        None => unreachable!(),
+    })
+}
 
+/// Like `count_arrangements_impl`, but with the first spring being `first` instead of the
+/// first element of `springs`, so that unknown springs don't need a new key
+fn count_with_first<'a>(
+    cache: &mut Cache<'a>,
+    first: char,
+    rest: &'a [char],
+    numbers: &'a [usize],
+    broken_count: usize,
+) -> usize {
+    match (first, rest.first()) {
+        ('.', Some(_)) => match (numbers, broken_count) {
+            (_, 0) => count_arrangements_impl(cache, rest, numbers, 0),
             ([n, ..], _) if *n == broken_count => {
-                count_arrangements_impl(&springs[1..], &numbers[1..], 0, cache)
+                count_arrangements_impl(cache, rest, &numbers[1..], 0)
             }
             _ => 0,
         },
-        (Some('.'), None) => match (numbers, broken_count) {
+        ('.', None) => match (numbers, broken_count) {
             ([n], _) if broken_count == *n => 1,
             ([], 0) => 1,
             _ => 0,
         },
-        (Some('#'), None) => match numbers {
+        ('#', None) => match numbers {
             [n] if broken_count + 1 == *n => 1,
             _ => 0,
         },
-        (Some('#'), Some(_)) => count_arrangements_impl(
-            &springs[1..],
-            numbers,
-            broken_count + 1,
-            cache,
-        ),
-        (Some('?'), _) => {
-            count_arrangements_impl(
-                &std::iter::once('#').chain(springs[1..].iter().copied()).collect_vec(),
-                numbers,
-                broken_count,
-                cache,
-            ) + count_arrangements_impl(
-                &std::iter::once('.').chain(springs[1..].iter().copied()).collect_vec(),
-                numbers,
-                broken_count,
-                cache,
-            )
-        }
+        ('#', Some(_)) => count_arrangements_impl(cache, rest, numbers, broken_count + 1),
         _ => unreachable!(),
-    };
-
-    cache.insert(CacheKey(springs.to_vec(), numbers.to_vec(), broken_count), result);
-
-    result
+    }
 }
 
 #[aoc_main]
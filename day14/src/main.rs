@@ -1,8 +1,7 @@
 #![feature(let_chains)]
-use std::collections::HashMap;
-
 use aoc_derive::aoc_main;
 use itertools::Itertools;
+use utils::cycle::CycleFinder;
 use utils::grid::Grid;
 use utils::math::Vec2D;
 use utils::*;
@@ -50,21 +49,6 @@ impl Platform {
             .map(|(i, row)| (i + 1) * row.filter(|(_, &c)| c == 'O').count())
             .sum()
     }
-
-    fn find_cycle(&self) -> (usize, usize) {
-        let mut grid = self.clone();
-        let mut visited = HashMap::new();
-        for i in 0.. {
-            grid.do_spin_cycle();
-
-            if let Some(&prev) = visited.get(&grid) {
-                return (prev, i - prev);
-            }
-
-            visited.insert(grid.clone(), i);
-        }
-        unreachable!()
-    }
 }
 
 #[aoc_main]
@@ -72,11 +56,13 @@ fn solve(input: Input) -> impl Into<Solution> {
     let mut grid_part1: Platform = input.char_grid().into();
     grid_part1.move_rocks((0, -1));
 
-    let mut grid_part2 = grid_part1.clone();
-    let (cycle_start, cycle_period) = grid_part2.find_cycle();
-    for _ in 0..(cycle_start + (1_000_000_000 - cycle_start) % cycle_period) {
-        grid_part2.do_spin_cycle();
-    }
+    let mut spin_cycles = CycleFinder::new(grid_part1.clone(), |platform: &Platform| {
+        let mut platform = platform.clone();
+        platform.do_spin_cycle();
+        platform
+    });
+    spin_cycles.hashed();
+    let grid_part2 = spin_cycles.nth_state(1_000_000_000);
 
     (grid_part1.get_load(), grid_part2.get_load())
 }
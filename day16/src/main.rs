@@ -1,43 +1,57 @@
 use std::collections::HashSet;
 
-use aoc_derive::aoc_main;
+use aoc_derive::{aoc_main, GridCell};
 use itertools::Itertools;
 use utils::grid::Grid;
 use utils::math::Vec2D;
 use utils::*;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, GridCell)]
+enum Tile {
+    #[default]
+    #[cell = '.']
+    Empty,
+    #[cell = '-']
+    HorizontalSplitter,
+    #[cell = '|']
+    VerticalSplitter,
+    #[cell = '/']
+    Mirror,
+    #[cell = '\\']
+    BackMirror,
+}
+
 fn count_energized_tiles(
     initial_pos: impl Into<Vec2D>,
     initial_heading: impl Into<Vec2D>,
-    grid: &Grid<char>,
+    grid: &Grid<Tile>,
 ) -> usize {
     let mut visited = HashSet::new();
     cast_rays(initial_pos.into(), initial_heading.into(), grid, &mut visited);
     visited.iter().unique_by(|(pos, _)| pos).count()
 }
 
-fn cast_rays(pos: Vec2D, heading: Vec2D, grid: &Grid<char>, visited: &mut HashSet<(Vec2D, Vec2D)>) {
+fn cast_rays(pos: Vec2D, heading: Vec2D, grid: &Grid<Tile>, visited: &mut HashSet<(Vec2D, Vec2D)>) {
     if visited.contains(&(pos, heading)) {
         return;
     }
 
     let (new_heading1, maybe_new_heading2) = match (grid.get(pos), heading.xy_tuple()) {
         (None, _) => return,
-        (Some('-'), (1, 0) | (-1, 0)) => (heading, None),
-        (Some('|'), (0, 1) | (0, -1)) => (heading, None),
-
-        (Some('-'), (0, 1) | (0, -1)) => (heading.rotated_left(), Some(heading.rotated_right())),
-        (Some('|'), (1, 0) | (-1, 0)) => (heading.rotated_left(), Some(heading.rotated_right())),
+        (Some(Tile::HorizontalSplitter), (1, 0) | (-1, 0)) => (heading, None),
+        (Some(Tile::VerticalSplitter), (0, 1) | (0, -1)) => (heading, None),
 
-        (Some('/'), (1, 0) | (-1, 0)) => (heading.rotated_left(), None),
-        (Some('/'), (0, 1) | (0, -1)) => (heading.rotated_right(), None),
+        (Some(Tile::HorizontalSplitter | Tile::VerticalSplitter), _) => {
+            (heading.rotated_left(), Some(heading.rotated_right()))
+        }
 
-        (Some('\\'), (1, 0) | (-1, 0)) => (heading.rotated_right(), None),
-        (Some('\\'), (0, 1) | (0, -1)) => (heading.rotated_left(), None),
+        (Some(Tile::Mirror), (1, 0) | (-1, 0)) => (heading.rotated_left(), None),
+        (Some(Tile::Mirror), _) => (heading.rotated_right(), None),
 
-        (Some('.'), _) => (heading, None),
+        (Some(Tile::BackMirror), (1, 0) | (-1, 0)) => (heading.rotated_right(), None),
+        (Some(Tile::BackMirror), _) => (heading.rotated_left(), None),
 
-        (Some(_), _) => unreachable!(),
+        (Some(Tile::Empty), _) => (heading, None),
     };
 
     visited.insert((pos, heading));
@@ -50,7 +64,7 @@ fn cast_rays(pos: Vec2D, heading: Vec2D, grid: &Grid<char>, visited: &mut HashSe
 
 #[aoc_main]
 fn solve(input: Input) -> impl Into<Solution> {
-    let grid = input.char_grid();
+    let (grid, _) = input.grid_of::<Tile>();
 
     let part1 = count_energized_tiles((0, 0), (1, 0), &grid);
 
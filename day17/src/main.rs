@@ -1,13 +1,15 @@
 use aoc_derive::aoc_main;
-use utils::graphs::{dijkstra, WeightedGraph};
+use utils::graphs::{
+    astar_with, manhattan_heuristic, BucketQueue, DenseNodeMap, IndexedGraph, WeightedGraph,
+};
 use utils::grid::Grid;
-use utils::math::Vec2D;
+use utils::math::{Direction, Vec2D};
 use utils::*;
 
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord, derive_more::Constructor)]
 struct Node {
     pos: Vec2D,
-    heading: Vec2D,
+    heading: Direction,
     straights: u8,
 }
 
@@ -31,13 +33,13 @@ impl WeightedGraph for Map {
                 node.straights + 1,
             )),
             (node.straights >= self.min_straight).then_some(Node::new(
-                node.pos + node.heading.rotated_left(),
-                node.heading.rotated_left(),
+                node.pos + node.heading.turn_left(),
+                node.heading.turn_left(),
                 0,
             )),
             (node.straights >= self.min_straight).then_some(Node::new(
-                node.pos + node.heading.rotated_right(),
-                node.heading.rotated_right(),
+                node.pos + node.heading.turn_right(),
+                node.heading.turn_right(),
                 0,
             )),
         ]
@@ -47,20 +49,43 @@ impl WeightedGraph for Map {
     }
 }
 
+impl IndexedGraph for Map {
+    fn num_nodes(&self) -> usize {
+        self.grid.num_rows() * self.grid.num_cols() * 4 * (self.max_straight as usize + 1)
+    }
+
+    fn index(&self, node: &Node) -> usize {
+        let cell = node.pos.y as usize * self.grid.num_cols() + node.pos.x as usize;
+        let heading = Direction::ORTHOGONAL.iter().position(|&d| d == node.heading).unwrap();
+        (cell * 4 + heading) * (self.max_straight as usize + 1) + node.straights as usize
+    }
+}
+
+fn min_heat_loss(map: &Map, target: Vec2D) -> usize {
+    let start1 = Node::new(Vec2D::new(0, 0), Direction::East, 0);
+    let start2 = Node::new(Vec2D::new(0, 0), Direction::South, 0);
+
+    // Every block costs at least 1, so the Manhattan distance never overestimates
+    astar_with(
+        map,
+        [start1, start2],
+        |node| node.pos == target && node.straights >= map.min_straight,
+        manhattan_heuristic(target, 1, |node: &Node| node.pos),
+        DenseNodeMap::new(map),
+        BucketQueue::new(),
+    )
+    .distance()
+    .unwrap()
+}
+
 #[aoc_main]
 fn solve(input: Input) -> impl Into<Solution> {
     let map1 = Map { grid: input.parse_grid_from_characters(), min_straight: 0, max_straight: 2 };
     let map2 = Map { grid: input.parse_grid_from_characters(), min_straight: 3, max_straight: 9 };
 
-    let start1 = Node::new(Vec2D::new(0, 0), Vec2D::new(1, 0), 0);
-    let start2 = Node::new(Vec2D::new(0, 0), Vec2D::new(0, 1), 0);
-    let target = (map1.grid.num_cols() - 1, map1.grid.num_rows() - 1);
+    let target = Vec2D::new(map1.grid.num_cols() as i64 - 1, map1.grid.num_rows() as i64 - 1);
 
-    (
-        dijkstra(&map1, [start1, start2], |node| node.pos == target).unwrap(),
-        dijkstra(&map2, [start1, start2], |node| node.pos == target && node.straights >= 3)
-            .unwrap(),
-    )
+    (min_heat_loss(&map1, target), min_heat_loss(&map2, target))
 }
 
 #[cfg(test)]
@@ -1,31 +1,11 @@
 use aoc_derive::aoc_main;
 use itertools::Itertools;
 use lazy_regex::regex_find;
-use utils::math::Vec2D;
+use utils::math::{Direction, Polygon, Vec2D};
 use utils::*;
 
-#[derive(Debug, Clone, Copy, parse_display::FromStr)]
-enum Dir {
-    R,
-    L,
-    D,
-    U,
-}
-
-impl Dir {
-    fn heading(&self) -> Vec2D {
-        match self {
-            Dir::R => (1, 0),
-            Dir::L => (-1, 0),
-            Dir::D => (0, 1),
-            Dir::U => (0, -1),
-        }
-        .into()
-    }
-}
-
 #[derive(Debug, Clone)]
-struct Instruction(Dir, i64);
+struct Instruction(Direction, i64);
 
 impl Instruction {
     fn parse_part1(s: &str) -> Self {
@@ -37,10 +17,10 @@ impl Instruction {
         let color_str = &regex_find!(r"#[a-z,0-9]+", s).unwrap()[1..];
         Self(
             match &color_str[5..] {
-                "0" => Dir::R,
-                "1" => Dir::D,
-                "2" => Dir::L,
-                "3" => Dir::U,
+                "0" => Direction::East,
+                "1" => Direction::South,
+                "2" => Direction::West,
+                "3" => Direction::North,
                 _ => unreachable!(),
             },
             i64::from_str_radix(&color_str[..5], 16).unwrap(),
@@ -49,25 +29,12 @@ impl Instruction {
 }
 
 fn area(instructions: Vec<Instruction>) -> i64 {
-    use Dir::*;
-    let vertices = instructions.into_iter().circular_tuple_windows().scan(
+    // The trench is one tile wide, so we need all tiles on the boundary as well
+    Polygon::from_walk(
         Vec2D::zero(),
-        |pos, (Instruction(dir, dist), Instruction(next_dir, _))| {
-            *pos += dist * dir.heading();
-            Some(
-                *pos + match (dir, next_dir) {
-                    (R, D) | (D, R) => (1, 0),
-                    (D, L) | (L, D) => (1, 1),
-                    (L, U) | (U, L) => (0, 1),
-                    (U, R) | (R, U) => (0, 0),
-                    _ => unreachable!("always expect a turn!"),
-                },
-            )
-        },
-    );
-
-    // shoelace formula
-    vertices.tuple_windows().map(|(p1, p2)| p1.x * p2.y - p1.y * p2.x).sum::<i64>() / 2
+        instructions.into_iter().map(|Instruction(dir, dist)| (dir, dist)),
+    )
+    .lattice_points()
 }
 
 #[aoc_main]
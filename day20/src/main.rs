@@ -6,6 +6,7 @@ use std::{
 
 use aoc_derive::aoc_main;
 use itertools::Itertools;
+use utils::math::number_theory::combine_cycles;
 use utils::*;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, derive_more::Display)]
@@ -142,6 +143,14 @@ impl<'a> Network<'a> {
         }
     }
 
+    /// The first button press in which `tracked_output` sends a high pulse, and the number of
+    /// presses until the next one.
+    ///
+    /// This can't use `utils::cycle::CycleFinder`: that needs a state which repeats, but the
+    /// state of the whole network only repeats after the lcm of the periods of all tracked
+    /// outputs, which is the answer we're looking for. A key that only projects onto the hit
+    /// times wouldn't determine the future either. So this relies on the inputs being built
+    /// as counters that send high pulses periodically from the start.
     fn find_high_cycle(mut self, tracked_output: &str) -> (usize, usize) {
         let mut cycle_start = None;
         let mut i = 1;
@@ -170,21 +179,6 @@ fn part1(input: &Input) -> usize {
     network.high_pulse_count * network.low_pulse_count
 }
 
-fn find_cycle_match(
-    (start1, cycle1): (usize, usize),
-    (start2, cycle2): (usize, usize),
-) -> (usize, usize) {
-    let mut i = start1;
-    let mut j = start2;
-    loop {
-        if i == j {
-            return (i, cycle1 * cycle2);
-        }
-        i += cycle1;
-        j += cycle2;
-    }
-}
-
 #[aoc_main]
 fn solve(input: Input) -> impl Into<Solution> {
     let network = Network::new(&input);
@@ -232,12 +226,11 @@ fn solve(input: Input) -> impl Into<Solution> {
         })
         .collect_vec();
 
-    (
-        part1(&input),
-        cycles
-            .iter()
-            .skip(1)
-            .fold(*cycles.first().unwrap(), |lhs, rhs| find_cycle_match(lhs, *rhs))
-            .1,
-    )
+    // rx gets a low pulse in the first press where all conjunctions send a high one. Each of
+    // them first does so after one period, so this is the lcm of the periods.
+    let part2 = combine_cycles(cycles.iter().map(|&(start, cycle)| (start as i64, cycle as i64)))
+        .expect("the conjunctions never send a high pulse in the same press")
+        .0;
+
+    (part1(&input), part2)
 }
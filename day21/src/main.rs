@@ -1,8 +1,7 @@
 use aoc_derive::aoc_main;
-use itertools::Itertools;
-use std::collections::HashSet;
-use utils::grid::Grid;
-use utils::math::Vec2D;
+use utils::graphs::{BfsLayers, UnweightedGraph};
+use utils::grid::{BitGrid, Grid};
+use utils::math::{ForwardDifferences, Vec2D};
 use utils::*;
 
 fn wrap_pos_to_grid(pos: &Vec2D, grid: &Grid<char>) -> Vec2D {
@@ -15,68 +14,78 @@ fn wrap_pos_to_grid(pos: &Vec2D, grid: &Grid<char>) -> Vec2D {
 }
 
 fn part1(grid: &Grid<char>, max_steps: usize) -> usize {
-    let mut positions =
-        HashSet::from([grid.iter().find_map(|(pos, &c)| (c == 'S').then_some(pos)).unwrap()]);
+    let start = grid.iter().find_map(|(pos, &c)| (c == 'S').then_some(pos)).unwrap();
+    let open = BitGrid::from_grid(grid, |&c| c != '#');
+
+    let mut positions = BitGrid::from_positions(grid.num_rows(), grid.num_cols(), [start]);
     for _ in 0..max_steps {
-        positions = positions
-            .into_iter()
-            .flat_map(|pos| {
-                pos.orthogonal_neighbors()
-                    .filter(|n| grid.get(*n) != Some(&'#'))
-                    .collect_vec()
-                    .into_iter()
-            })
-            .collect();
+        positions = positions.orthogonal_spread() & &open;
     }
-    positions.len()
+    positions.count_ones()
 }
 
-fn part2(grid: &Grid<char>, max_steps: usize) -> usize {
-    let start = grid.iter().find_map(|(pos, &c)| (c == 'S').then_some(pos)).unwrap();
-
-    let mut step = 0;
-    let mut visited = HashSet::new();
-
-    let mut next = HashSet::new();
-    next.insert(start);
+/// The garden repeated infinitely in all directions
+struct InfiniteGarden<'a>(&'a Grid<char>);
 
-    let mut reachable = if max_steps % 2 == 0 { 1 } else { 0 };
+impl UnweightedGraph for InfiniteGarden<'_> {
+    type Node = Vec2D;
 
-    loop {
-        let mut neighbors = HashSet::new();
-        for node in next {
-            visited.insert(node);
-
-            for n in node.orthogonal_neighbors() {
-                if grid[wrap_pos_to_grid(&n, grid)] != '#' && !visited.contains(&n) {
-                    neighbors.insert(n);
-                }
-            }
-        }
-
-        if step % 2 != max_steps % 2 {
-            reachable += neighbors.len();
-        }
+    fn neighbors<'a, 'b: 'a>(&'a self, node: &'b Vec2D) -> impl Iterator<Item = Vec2D> + 'a {
+        node.orthogonal_neighbors().filter(|n| self.0[wrap_pos_to_grid(n, self.0)] != '#')
+    }
+}
 
-        if step == max_steps {
-            return reachable;
-        }
+fn part2(grid: &Grid<char>, max_steps: usize) -> usize {
+    let start = grid.iter().find_map(|(pos, &c)| (c == 'S').then_some(pos)).unwrap();
 
-        next = neighbors;
-        step += 1;
-    }
+    // We can always step back and forth, so every plot at a distance with the same parity as
+    // max_steps is reachable
+    BfsLayers::new(&InfiniteGarden(grid), [start])
+        .take(max_steps + 1)
+        .skip(max_steps % 2)
+        .step_by(2)
+        .map(|layer| layer.len())
+        .sum()
 }
 
 #[aoc_main]
 fn solve(input: Input) -> impl Into<Solution> {
     let grid = input.char_grid();
 
-    // Throw this into wolframalpha to obtain the quadratic fit below
-    for x in 0..6 {
-        println!("({}, {}), ", x, part2(&grid, 65 + x * 131));
-    }
+    // The number of reachable plots grows quadratically in the number of grid repetitions we
+    // can walk through, so sample it for a few of them. One more sample than needed confirms
+    // that the second differences are constant.
+    let samples = (0..4).map(|x| part2(&grid, 65 + x * 131) as i64).collect::<Vec<_>>();
+    let differences = ForwardDifferences::new(&samples);
+    assert_eq!(differences.degree(), Some(2), "reachable plots don't grow quadratically");
 
-    let x = 26501365_i64 / 131;
+    (part1(&grid, 64), differences.at(26501365_i64 / 131))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_example() {
+        let grid = Input::from(
+            r#"...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+..........."#,
+        )
+        .char_grid();
 
-    (part1(&grid, 64), 14590 * x * x + 14694 * x + 3691)
+        assert_eq!(part1(&grid, 6), 16);
+        for (steps, plots) in [(6, 16), (10, 50), (50, 1594), (100, 6536)] {
+            assert_eq!(part2(&grid, steps), plots);
+        }
+    }
 }
@@ -5,6 +5,7 @@ use std::str::FromStr;
 
 use aoc_derive::aoc_main;
 use itertools::Itertools;
+use utils::math::Box2D;
 use utils::ParseInput;
 use utils::*;
 
@@ -16,10 +17,12 @@ struct Brick {
 }
 
 impl Brick {
+    fn footprint(&self) -> Box2D {
+        Box2D::new((*self.x.start(), *self.y.start()), (*self.x.end(), *self.y.end()))
+    }
+
     fn x_and_y_overlap(&self, other: &Brick) -> bool {
-        let no_overlap = (self.x.end() < other.x.start() || self.x.start() > other.x.end())
-            || (self.y.end() < other.y.start() || self.y.start() > other.y.end());
-        !no_overlap
+        self.footprint().overlaps(&other.footprint())
     }
 }
 
@@ -1,19 +1,19 @@
 use aoc_derive::aoc_main;
 use itertools::Itertools;
 use lazy_regex::regex;
-use rangemap::{RangeMap, RangeSet};
+use utils::math::{IntervalMap, IntervalSet, Line1D};
 use utils::{Input, Solution, RegexHelper};
 
-fn parse_range_map(s: &[&str]) -> RangeMap<u64, u64> {
+fn parse_interval_map(s: &[&str]) -> IntervalMap {
     s.iter()
         .skip(1)
         .map(|line| {
-            let (dest_range_start, source_range_start, len) = regex!(r"\d+")
-                .find_parse_into_tuple(line);
+            let (dest_range_start, source_range_start, len): (i64, i64, i64) =
+                regex!(r"\d+").find_parse_into_tuple(line);
 
             (
-                source_range_start..source_range_start + len,
-                dest_range_start,
+                Line1D::new((source_range_start, source_range_start + len - 1)),
+                dest_range_start - source_range_start,
             )
         })
         .collect()
@@ -24,68 +24,31 @@ fn solve(input: Input) -> impl Into<Solution> {
     let lines = input.lines().collect_vec();
     let mut blocks = lines.split(|line| line.is_empty());
 
-    let seeds: Vec<u64> = regex!(r"\d+")
+    let seeds: Vec<i64> = regex!(r"\d+")
         .find_iter_parse(blocks.next().unwrap().first().unwrap())
         .collect_vec();
 
-    let type_maps = blocks.map(parse_range_map).collect_vec();
+    let type_maps = blocks.map(parse_interval_map).collect_vec();
 
     let part1 = seeds
         .iter()
-        .map(|seed| {
-            type_maps.iter().fold(*seed, |from, type_map| {
-                match type_map.get_key_value(&from) {
-                    Some((source_range, dest_start)) => dest_start + (from - source_range.start),
-                    None => from,
-                }
-            })
-        })
+        .map(|seed| type_maps.iter().fold(*seed, |from, type_map| type_map.get(from)))
         .min()
         .unwrap();
 
-    let part2 = seeds
+    let initial_ranges: IntervalSet = seeds
         .into_iter()
         .tuples()
-        .map(|(seed_start, len)| {
-            let initial_range = RangeSet::from_iter(std::iter::once(seed_start..seed_start + len));
-            type_maps
-                .iter()
-                .fold(initial_range, |current_ranges, type_map| {
-                    current_ranges
-                        .iter()
-                        // For every of our ranges, find the ranges in the type_map that overlap
-                        // with it.
-                        .flat_map(|current_range| {
-                            type_map
-                                .overlapping(current_range)
-                                .zip(std::iter::repeat(current_range))
-                        })
-                        // Calculate the intersection between our range and the overlapping range,
-                        // then map it to the resulting destination range
-                        .map(|((range_with_overlap, dest_start), current_range)| {
-                            let intersection = current_range.start.max(range_with_overlap.start)
-                                ..current_range.end.min(range_with_overlap.end);
-
-                            let new_start =
-                                dest_start + (intersection.start - range_with_overlap.start);
-
-                            new_start..new_start + intersection.try_len().unwrap() as u64
-                        })
-                        // RangeMap::gaps() gives us the ranges where there's no overlap.
-                        // Those are not modified, so simply add them our next set
-                        .chain(current_ranges.iter().flat_map(|r| type_map.gaps(r)))
-                        .collect()
-                })
-                // After the fold, we have RangeSet containing our initial seed range mapped to
-                // location ranges.
-                // Since RangeSet is ordered, the minimum is simply the start of the first range
-                .iter()
-                .next()
-                .unwrap()
-                .start
-        })
-        .min()
-        .unwrap();
+        .map(|(seed_start, len)| Line1D::new((seed_start, seed_start + len - 1)))
+        .collect();
+
+    // Since IntervalSet is ordered, the minimum is simply the start of the first range
+    let part2 = type_maps
+        .iter()
+        .fold(initial_ranges, |current_ranges, type_map| type_map.apply(&current_ranges))
+        .bounds()
+        .unwrap()
+        .start();
 
     (part1, part2)
 }
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use aoc_derive::aoc_main;
 use itertools::Itertools;
 use lazy_regex::regex;
+use utils::cycle::CycleFinder;
+use utils::math::number_theory::combine_cycles;
 use utils::*;
 
 #[derive(Debug)]
@@ -40,32 +42,36 @@ impl Maze<'_> {
             .count()
     }
 
-    fn find_cycle(&self, start: &str) -> (usize, usize) {
-        let mut first_target_hit = None;
-        let mut current_node = start;
+    /// The first time the ghost from `start` is on a Z node (once the states of the walk
+    /// repeat), and the time until it's on one again. None if it never gets to a Z node again, or
+    /// not periodically.
+    fn find_cycle(&self, start: &str) -> Option<(usize, usize)> {
+        let step = |&(node, i): &(&str, usize)| {
+            (self.next_node(node, i), (i + 1) % self.instructions.len())
+        };
+        let mut finder = CycleFinder::new((start, 0), step);
+        let cycle = finder.hashed();
 
-        (0..)
-            .find_map(|i| {
-                current_node = self.next_node(current_node, i);
-
-                if current_node.ends_with('Z') {
-                    if let Some(first_target_hit) = first_target_hit {
-                        return Some((first_target_hit + 1, i - first_target_hit));
-                    } else {
-                        first_target_hit = Some(i);
-                    }
-                }
-                None
-            })
-            .unwrap()
+        let hits = finder
+            .states()
+            .enumerate()
+            .skip(cycle.start)
+            .take(cycle.period)
+            .filter_map(|(time, (node, _))| node.ends_with('Z').then_some(time))
+            .collect_vec();
+        if hits.is_empty() || cycle.period % hits.len() != 0 {
+            return None;
+        }
+
+        // Several hits per period are fine as long as they're evenly spaced
+        let spacing = cycle.period / hits.len();
+        hits.iter().tuple_windows().all(|(a, b)| b - a == spacing).then_some((hits[0], spacing))
     }
 }
 
-#[aoc_main]
-fn solve(input: Input) -> impl Into<Solution> {
-    let (instructions, nodes) = input.raw.split("\n\n").collect_tuple().unwrap();
-
-    let maze = Maze {
+fn parse_maze(input: &str) -> Maze<'_> {
+    let (instructions, nodes) = input.split("\n\n").collect_tuple().unwrap();
+    Maze {
         nodes: nodes
             .lines()
             .map(|line| {
@@ -75,24 +81,74 @@ fn solve(input: Input) -> impl Into<Solution> {
             })
             .collect(),
         instructions: instructions.chars().collect(),
-    };
+    }
+}
 
-    let nodes_part2 = maze.nodes.keys().filter(|name| name.ends_with('A')).copied().collect_vec();
+#[aoc_main]
+fn solve(input: Input) -> impl Into<Solution> {
+    let maze = parse_maze(&input.raw);
 
-    let node_times =
-        nodes_part2.into_iter().map(|start_node| maze.find_cycle(start_node)).collect_vec();
+    let nodes_part2 = maze.nodes.keys().filter(|name| name.ends_with('A')).copied().collect_vec();
 
-    let (hit_for_max_cycle_time, max_cycle_time) =
-        node_times.iter().copied().max_by_key(|(_, cycle)| *cycle).unwrap();
+    let node_times = nodes_part2
+        .into_iter()
+        .map(|start_node| maze.find_cycle(start_node))
+        .collect::<Option<Vec<_>>>()
+        .expect("every ghost should get to Z nodes periodically");
 
-    let part2 = (hit_for_max_cycle_time..)
-        .step_by(max_cycle_time)
-        .find(|i| {
-            node_times
-                .iter()
-                .all(|(first_hit, cycle)| i >= first_hit && (i - first_hit) % cycle == 0)
-        })
-        .unwrap();
+    let part2 = combine_cycles(
+        node_times.iter().map(|&(first_hit, cycle)| (first_hit as i64, cycle as i64)),
+    )
+    .unwrap()
+    .0;
 
     (maze.solve_part1(), part2)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_examples() {
+        use utils::assert_example;
+        assert_example!(
+            r#"RL
+
+AAA = (BBB, CCC)
+BBB = (DDD, EEE)
+CCC = (ZZZ, GGG)
+DDD = (DDD, DDD)
+EEE = (EEE, EEE)
+GGG = (GGG, GGG)
+ZZZ = (ZZZ, ZZZ)"#,
+            2,
+            2
+        );
+    }
+
+    #[test]
+    fn ghost_cycles() {
+        let maze = parse_maze(
+            r#"LR
+
+11A = (11B, XXX)
+11B = (XXX, 11Z)
+11Z = (11B, XXX)
+22A = (22B, XXX)
+22B = (22C, 22C)
+22C = (22Z, 22Z)
+22Z = (22B, 22B)
+33A = (33B, 33B)
+33B = (33A, 33A)
+XXX = (XXX, XXX)"#,
+        );
+        assert_eq!(maze.find_cycle("11A"), Some((2, 2)));
+        // Two hits per cycle of the walk, which also depends on the instruction index
+        assert_eq!(maze.find_cycle("22A"), Some((3, 3)));
+        assert_eq!(combine_cycles([(2, 2), (3, 3)]), Some((6, 6)));
+
+        // Never on a Z node
+        assert_eq!(maze.find_cycle("33A"), None);
+    }
+}
@@ -1,24 +1,16 @@
 use aoc_derive::aoc_main;
 use itertools::Itertools;
+use utils::math::ForwardDifferences;
 use utils::*;
 
-fn extrapolate(hist: &[i64]) -> i64 {
-    if hist.iter().all(|&h| h == 0) {
-        return 0;
-    }
-
-    let diffs = hist.iter().tuple_windows().map(|(&a, &b)| b - a).collect_vec();
-
-    hist.last().unwrap() + extrapolate(&diffs)
-}
-
 #[aoc_main]
 fn solve(input: Input) -> impl Into<Solution> {
     let hists = input.lines().map(|l| extract_numbers(l).collect_vec()).collect_vec();
+    let differences = hists.iter().map(|h| ForwardDifferences::new(h)).collect_vec();
 
     (
-        hists.iter().map(|h| extrapolate(h)).sum::<i64>(),
-        hists.into_iter().update(|h| h.reverse()).map(|h| extrapolate(&h)).sum::<i64>(),
+        hists.iter().zip(&differences).map(|(h, d)| d.at(h.len() as i64)).sum::<i64>(),
+        differences.iter().map(|d| d.at(-1)).sum::<i64>(),
     )
 }
 
@@ -0,0 +1,310 @@
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+use crate::{
+    cycle::{CycleFinder, WholeState},
+    grid::Grid,
+    math::{Neighborhood, Vec2D},
+    sparse_grid::SparseGrid,
+};
+
+/// Something that can be stepped by a cellular automaton rule. The rule maps a cell and its
+/// neighbors (as given by the Neighborhood) to the cell's next state.
+pub trait Space: Clone {
+    type Cell;
+
+    /// Writes the next generation into `next`, overwriting its previous content
+    fn step_into<R>(&self, next: &mut Self, neighborhood: &Neighborhood, rule: &R)
+    where
+        R: Fn(&Self::Cell, &[&Self::Cell]) -> Self::Cell;
+
+    fn par_step_into<R>(&self, next: &mut Self, neighborhood: &Neighborhood, rule: &R)
+    where
+        R: Fn(&Self::Cell, &[&Self::Cell]) -> Self::Cell + Sync,
+        Self::Cell: Send + Sync;
+}
+
+/// Neighbors outside of the grid are not passed to the rule
+impl<T: Clone> Space for Grid<T> {
+    type Cell = T;
+
+    fn step_into<R>(&self, next: &mut Self, neighborhood: &Neighborhood, rule: &R)
+    where
+        R: Fn(&T, &[&T]) -> T,
+    {
+        let mut neighbors = Vec::new();
+        for (pos, cell) in self.iter() {
+            neighbors.clear();
+            neighbors.extend(self.neighbors_in(&pos, neighborhood).map(|n| &self[n]));
+            next[pos] = rule(cell, &neighbors);
+        }
+    }
+
+    fn par_step_into<R>(&self, next: &mut Self, neighborhood: &Neighborhood, rule: &R)
+    where
+        R: Fn(&T, &[&T]) -> T + Sync,
+        T: Send + Sync,
+    {
+        next.inner_mut().par_iter_mut().enumerate().for_each(|(y, row)| {
+            let mut neighbors = Vec::new();
+            for (x, next_cell) in row.iter_mut().enumerate() {
+                let pos = Vec2D::from((x, y));
+                neighbors.clear();
+                neighbors.extend(self.neighbors_in(&pos, neighborhood).map(|n| &self[n]));
+                *next_cell = rule(&self[pos], &neighbors);
+            }
+        });
+    }
+}
+
+/// An infinite grid where every position that is not stored in `cells` has the `background`
+/// value. The rule must map a background cell with only background neighbors to background.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnboundedGrid<T> {
+    pub cells: SparseGrid<T>,
+    pub background: T,
+}
+
+impl<T> UnboundedGrid<T> {
+    pub fn new(cells: SparseGrid<T>, background: T) -> Self {
+        Self { cells, background }
+    }
+
+    pub fn get(&self, pos: &Vec2D) -> &T {
+        self.cells.get(*pos).unwrap_or(&self.background)
+    }
+
+    /// Positions whose next state might differ from the background
+    fn candidates(&self, neighborhood: &Neighborhood) -> HashSet<Vec2D> {
+        let offsets = neighborhood.offsets();
+        self.cells
+            .iter_unordered()
+            .flat_map(|(pos, _)| std::iter::once(pos).chain(offsets.iter().map(move |&o| pos - o)))
+            .collect()
+    }
+
+    fn next_cell<R>(&self, pos: &Vec2D, neighborhood: &Neighborhood, rule: &R) -> T
+    where
+        R: Fn(&T, &[&T]) -> T,
+    {
+        let neighbors = pos.neighbors_in(neighborhood).map(|n| self.get(&n)).collect::<Vec<_>>();
+        rule(self.get(pos), &neighbors)
+    }
+}
+
+impl<T: Clone + PartialEq> Space for UnboundedGrid<T> {
+    type Cell = T;
+
+    fn step_into<R>(&self, next: &mut Self, neighborhood: &Neighborhood, rule: &R)
+    where
+        R: Fn(&T, &[&T]) -> T,
+    {
+        next.cells.clear();
+        next.background = self.background.clone();
+        for pos in self.candidates(neighborhood) {
+            let cell = self.next_cell(&pos, neighborhood, rule);
+            if cell != self.background {
+                next.cells.insert(pos, cell);
+            }
+        }
+    }
+
+    fn par_step_into<R>(&self, next: &mut Self, neighborhood: &Neighborhood, rule: &R)
+    where
+        R: Fn(&T, &[&T]) -> T + Sync,
+        T: Send + Sync,
+    {
+        next.background = self.background.clone();
+        next.cells.clear();
+        next.cells.par_extend(self.candidates(neighborhood).into_par_iter().filter_map(|pos| {
+            let cell = self.next_cell(&pos, neighborhood, rule);
+            (cell != self.background).then_some((pos, cell))
+        }));
+    }
+}
+
+/// Steps a Space generation by generation, using two buffers that are swapped after each step.
+/// As an iterator, it yields the initial state followed by all following generations, so
+/// `.nth(n)` is the state after n steps.
+pub struct Automaton<S, R> {
+    current: S,
+    buffer: S,
+    neighborhood: Neighborhood,
+    rule: R,
+    generation: usize,
+    yielded_initial: bool,
+}
+
+impl<S, R> Automaton<S, R>
+where
+    S: Space,
+    R: Fn(&S::Cell, &[&S::Cell]) -> S::Cell,
+{
+    pub fn new(initial: S, neighborhood: Neighborhood, rule: R) -> Self {
+        Self {
+            buffer: initial.clone(),
+            current: initial,
+            neighborhood,
+            rule,
+            generation: 0,
+            yielded_initial: false,
+        }
+    }
+
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    pub fn into_current(self) -> S {
+        self.current
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    pub fn step(&mut self) -> &S {
+        self.current.step_into(&mut self.buffer, &self.neighborhood, &self.rule);
+        self.swap_buffers()
+    }
+
+    pub fn par_step(&mut self) -> &S
+    where
+        R: Sync,
+        S::Cell: Send + Sync,
+    {
+        self.current.par_step_into(&mut self.buffer, &self.neighborhood, &self.rule);
+        self.swap_buffers()
+    }
+
+    pub fn step_n(&mut self, n: usize) -> &S {
+        for _ in 0..n {
+            self.step();
+        }
+        &self.current
+    }
+
+    /// Cycle detection over the generations, starting at the current one. Indices in the
+    /// found `Cycle` and in `nth_state` are relative to it.
+    pub fn into_cycle_finder(self) -> CycleFinder<S, impl FnMut(&S) -> S, WholeState<S>> {
+        let Self { current, neighborhood, rule, .. } = self;
+        CycleFinder::new(current, move |state: &S| {
+            let mut next = state.clone();
+            state.step_into(&mut next, &neighborhood, &rule);
+            next
+        })
+    }
+
+    fn swap_buffers(&mut self) -> &S {
+        std::mem::swap(&mut self.current, &mut self.buffer);
+        self.generation += 1;
+        &self.current
+    }
+}
+
+/// Every item is a clone of the whole state, so iterating costs a copy of the grid per
+/// generation on top of the step. To only look at the states, loop over `step()` and
+/// `current()`, which borrow them instead.
+impl<S, R> Iterator for Automaton<S, R>
+where
+    S: Space,
+    R: Fn(&S::Cell, &[&S::Cell]) -> S::Cell,
+{
+    type Item = S;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.yielded_initial {
+            self.yielded_initial = true;
+        } else {
+            self.step();
+        }
+        Some(self.current.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cycle::Cycle, Input};
+    use pretty_assertions::assert_eq;
+
+    fn game_of_life(cell: &char, neighbors: &[&char]) -> char {
+        match (cell, neighbors.iter().filter(|&&&c| c == '#').count()) {
+            ('#', 2 | 3) | ('.', 3) => '#',
+            _ => '.',
+        }
+    }
+
+    #[test]
+    fn grid() {
+        let blinker = Input::from(".....\n..#..\n..#..\n..#..\n.....").char_grid();
+        let mut automaton = Automaton::new(blinker.clone(), Neighborhood::Moore, game_of_life);
+
+        assert_eq!(automaton.step().to_string(), ".....\n.....\n.###.\n.....\n.....\n");
+        assert_eq!(automaton.par_step(), &blinker);
+        assert_eq!(automaton.generation(), 2);
+
+        let generations = Automaton::new(blinker.clone(), Neighborhood::Moore, game_of_life)
+            .take(3)
+            .collect::<Vec<_>>();
+        assert_eq!(generations[0], blinker);
+        assert_ne!(generations[1], blinker);
+        assert_eq!(generations[2], blinker);
+    }
+
+    #[test]
+    fn unbounded() {
+        let glider = Input::from(".#.\n..#\n###").char_sparse_grid();
+        let glider: SparseGrid<_> = glider.into_iter().filter(|&(_, c)| c == '#').collect();
+
+        let mut automaton = Automaton::new(
+            UnboundedGrid::new(glider.clone(), '.'),
+            Neighborhood::Moore,
+            game_of_life,
+        );
+        automaton.step_n(4);
+
+        // After 4 generations, the glider has moved by (1, 1)
+        let moved: SparseGrid<_> = glider.iter().map(|(pos, &c)| (pos + (1, 1), c)).collect();
+        assert_eq!(automaton.current().cells, moved);
+
+        let mut par_automaton =
+            Automaton::new(UnboundedGrid::new(glider, '.'), Neighborhood::Moore, game_of_life);
+        for _ in 0..4 {
+            par_automaton.par_step();
+        }
+        assert_eq!(par_automaton.into_current().cells, moved);
+    }
+
+    #[test]
+    fn cycle() {
+        // A block never changes, a blinker starting after one generation has period 2
+        let block = Input::from("....\n.##.\n.##.\n....").char_grid();
+        let mut finder =
+            Automaton::new(block, Neighborhood::Moore, game_of_life).into_cycle_finder();
+        assert_eq!(finder.hashed(), Cycle { start: 0, period: 1 });
+
+        let blinker = Input::from(".....\n..#..\n..#..\n..#..\n.....").char_grid();
+        let mut automaton = Automaton::new(blinker.clone(), Neighborhood::Moore, game_of_life);
+        automaton.step();
+        let mut finder = automaton.into_cycle_finder();
+        assert_eq!(finder.brent(), Cycle { start: 0, period: 2 });
+        assert_eq!(finder.nth_state(1_000_000_001), blinker);
+    }
+
+    #[test]
+    fn steps() {
+        // Positions reachable in exactly n steps, as in 2023 day 21
+        let garden = Input::from(".....\n.#...\n..S..\n...#.\n.....").char_grid();
+        let rule = |cell: &char, neighbors: &[&char]| match cell {
+            '#' => '#',
+            _ if neighbors.iter().any(|&&c| c == 'O' || c == 'S') => 'O',
+            _ => '.',
+        };
+        let mut automaton = Automaton::new(garden, Neighborhood::VonNeumann, rule);
+        let count = |grid: &Grid<char>| grid.iter().filter(|(_, &c)| c == 'O').count();
+        assert_eq!(count(automaton.step()), 4);
+        assert_eq!(count(automaton.step()), 7);
+    }
+}
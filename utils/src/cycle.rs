@@ -0,0 +1,233 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// The states x_start and x_(start + period) of a sequence are the same (and so are all states
+/// after that), with start and period as small as possible.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub period: usize,
+}
+
+impl Cycle {
+    /// The smallest index whose state is the same as the one at index n
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.period
+        }
+    }
+}
+
+/// Finds the cycle in the deterministic sequence x_0 = initial, x_(i+1) = step(x_i).
+///
+/// States are compared via a key, which is the whole state by default. A custom key (see
+/// `with_key`) can skip parts of the state that don't influence the rest of the sequence, like
+/// a step counter, but it has to determine the future: equal keys must lead to equal keys.
+pub struct CycleFinder<S, F, K> {
+    initial: S,
+    step: F,
+    key: K,
+    cycle: Option<Cycle>,
+}
+
+/// The key of `CycleFinder::new`, which compares whole states
+pub type WholeState<S> = fn(&S) -> S;
+
+impl<S: Clone, F: FnMut(&S) -> S> CycleFinder<S, F, WholeState<S>> {
+    pub fn new(initial: S, step: F) -> Self {
+        Self { initial, step, key: S::clone, cycle: None }
+    }
+}
+
+impl<S: Clone, F: FnMut(&S) -> S, K> CycleFinder<S, F, K> {
+    pub fn with_key<Key, K2: FnMut(&S) -> Key>(self, key: K2) -> CycleFinder<S, F, K2> {
+        CycleFinder { initial: self.initial, step: self.step, key, cycle: None }
+    }
+
+    /// The cycle found by the last call to one of the detectors
+    pub fn cycle(&self) -> Option<Cycle> {
+        self.cycle
+    }
+
+    /// Floyd's tortoise and hare: constant memory, but steps the sequence about three times as
+    /// often as `hashed`.
+    pub fn floyd<Key: PartialEq>(&mut self) -> Cycle
+    where
+        K: FnMut(&S) -> Key,
+    {
+        let Self { initial, step, key, .. } = self;
+        let mut tortoise = step(initial);
+        let mut hare = step(&tortoise);
+        while key(&tortoise) != key(&hare) {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            hare = step(&hare);
+        }
+
+        // The distance between the two is a multiple of the period now, so moving them in
+        // lockstep from the initial state and from the hare makes them meet at the start
+        let mut start = 0;
+        tortoise = initial.clone();
+        while key(&tortoise) != key(&hare) {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            start += 1;
+        }
+
+        let mut period = 1;
+        hare = step(&tortoise);
+        while key(&tortoise) != key(&hare) {
+            hare = step(&hare);
+            period += 1;
+        }
+
+        self.found(Cycle { start, period })
+    }
+
+    /// Brent's algorithm: constant memory like `floyd`, but needs fewer steps
+    pub fn brent<Key: PartialEq>(&mut self) -> Cycle
+    where
+        K: FnMut(&S) -> Key,
+    {
+        let Self { initial, step, key, .. } = self;
+
+        // Find the period by teleporting the tortoise to the hare at every power of two
+        let mut power = 1;
+        let mut period = 1;
+        let mut tortoise = initial.clone();
+        let mut hare = step(initial);
+        while key(&tortoise) != key(&hare) {
+            if power == period {
+                tortoise = hare.clone();
+                power *= 2;
+                period = 0;
+            }
+            hare = step(&hare);
+            period += 1;
+        }
+
+        // Then walk two states that are one period apart until they meet
+        let mut start = 0;
+        tortoise = initial.clone();
+        hare = initial.clone();
+        for _ in 0..period {
+            hare = step(&hare);
+        }
+        while key(&tortoise) != key(&hare) {
+            tortoise = step(&tortoise);
+            hare = step(&hare);
+            start += 1;
+        }
+
+        self.found(Cycle { start, period })
+    }
+
+    /// Remembers the index of every key in a HashMap, so each state is only computed once
+    pub fn hashed<Key: Hash + Eq>(&mut self) -> Cycle
+    where
+        K: FnMut(&S) -> Key,
+    {
+        let Self { initial, step, key, .. } = self;
+        let mut seen = HashMap::new();
+        let mut state = initial.clone();
+        for i in 0.. {
+            if let Some(start) = seen.insert(key(&state), i) {
+                return self.found(Cycle { start, period: i - start });
+            }
+            state = step(&state);
+        }
+        unreachable!()
+    }
+
+    fn found(&mut self, cycle: Cycle) -> Cycle {
+        self.cycle = Some(cycle);
+        cycle
+    }
+
+    /// x_0, x_1, ... without skipping anything, e.g. to look for events within one period
+    pub fn states(&mut self) -> impl Iterator<Item = S> + '_ {
+        let Self { initial, step, .. } = self;
+        std::iter::successors(Some(initial.clone()), move |state| Some(step(state)))
+    }
+
+    /// x_n, which skips all full periods if a cycle has been found already. Only the key is
+    /// guaranteed to be right in that case, since we jump to an earlier state with the same key.
+    pub fn nth_state(&mut self, n: usize) -> S {
+        let n = self.cycle.map_or(n, |cycle| cycle.reduce(n));
+        let mut state = self.initial.clone();
+        for _ in 0..n {
+            state = (self.step)(&state);
+        }
+        state
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Finder = CycleFinder<u64, fn(&u64) -> u64, WholeState<u64>>;
+
+    fn finder(initial: u64) -> Finder {
+        CycleFinder::new(initial, |x| (x * x + 1) % 255)
+    }
+
+    fn brute_force(initial: u64) -> Cycle {
+        let states = std::iter::successors(Some(initial), |x| Some((x * x + 1) % 255))
+            .take(1000)
+            .collect::<Vec<_>>();
+        let (start, end) = (0..states.len())
+            .flat_map(|end| (0..end).map(move |start| (start, end)))
+            .find(|&(start, end)| states[start] == states[end])
+            .unwrap();
+        Cycle { start, period: end - start }
+    }
+
+    #[test]
+    fn detectors_agree() {
+        for initial in 0..255 {
+            let expected = brute_force(initial);
+            assert_eq!(finder(initial).floyd(), expected, "floyd from {initial}");
+            assert_eq!(finder(initial).brent(), expected, "brent from {initial}");
+            assert_eq!(finder(initial).hashed(), expected, "hashed from {initial}");
+        }
+    }
+
+    #[test]
+    fn immediate_fixed_point() {
+        let mut finder = CycleFinder::new(7, |&x: &i32| x);
+        assert_eq!(finder.floyd(), Cycle { start: 0, period: 1 });
+        assert_eq!(finder.brent(), Cycle { start: 0, period: 1 });
+        assert_eq!(finder.hashed(), Cycle { start: 0, period: 1 });
+    }
+
+    #[test]
+    fn key_projection() {
+        // (counter, value): the counter never repeats, but doesn't influence the value
+        let step = |&(i, x): &(usize, u64)| (i + 1, (x * 3 + 1) % 10);
+        let mut finder = CycleFinder::new((0, 0), step).with_key(|&(_, x): &(usize, u64)| x);
+        let expected = Cycle { start: 0, period: 4 };
+        assert_eq!(finder.floyd(), expected);
+        assert_eq!(finder.brent(), expected);
+        assert_eq!(finder.hashed(), expected);
+        assert_eq!(finder.nth_state(1_000_000_001).1, 1);
+    }
+
+    #[test]
+    fn nth_state() {
+        let mut finder = finder(2);
+        let slow = finder.nth_state(1000);
+        assert_eq!(finder.cycle(), None);
+
+        let cycle = finder.brent();
+        assert_eq!(finder.cycle(), Some(cycle));
+        assert_eq!(finder.nth_state(1000), slow);
+        assert_eq!(cycle.reduce(1000), cycle.reduce(1000 + cycle.period));
+        assert_eq!(
+            finder.nth_state(1_000_000_000_000),
+            finder.nth_state(cycle.reduce(1_000_000_000_000))
+        );
+        assert_eq!(finder.states().nth(1000), Some(slow));
+    }
+}
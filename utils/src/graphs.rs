@@ -1,9 +1,17 @@
-use std::cmp::Reverse;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::hash::Hash;
 
-use priority_queue::PriorityQueue;
+use crate::math::Vec2D;
+
+mod compress;
+pub use compress::*;
+mod longest_path;
+pub use longest_path::*;
+mod node_map;
+pub use node_map::*;
+mod queue;
+pub use queue::*;
 
 pub type Cost = usize;
 
@@ -18,60 +26,139 @@ pub trait UnweightedGraph {
     ) -> impl Iterator<Item = Self::Node> + 'a;
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
-pub struct BfsResult<N: Node> {
+/// Breadth-first iteration over the layers of nodes at distance 0, 1, 2, ... from the start
+/// nodes. Each node shows up in exactly one layer. Works lazily, so the graph can be infinite.
+pub struct BfsLayers<'a, G: UnweightedGraph> {
+    graph: &'a G,
+    /// Distance and parent of every discovered node, start nodes have no parent
+    discovered: HashMap<G::Node, (usize, Option<G::Node>)>,
+    frontier: Vec<G::Node>,
     distance: Option<usize>,
-    visited: HashSet<N>,
 }
 
-pub fn bfs<N: Node>(
-    graph: &impl UnweightedGraph<Node = N>,
-    start: impl Into<N>,
-    end: impl Into<N>) -> BfsResult<N> {
-    bfs_impl(graph, start, Some(end.into()))
+impl<'a, G: UnweightedGraph> BfsLayers<'a, G> {
+    pub fn new<S: Into<G::Node>>(graph: &'a G, start_nodes: impl IntoIterator<Item = S>) -> Self {
+        let mut discovered = HashMap::new();
+        let mut frontier = Vec::new();
+        for start_node in start_nodes {
+            let start_node = start_node.into();
+            if discovered.insert(start_node.clone(), (0, None)).is_none() {
+                frontier.push(start_node);
+            }
+        }
+        Self { graph, discovered, frontier, distance: None }
+    }
 }
 
+impl<G: UnweightedGraph> Iterator for BfsLayers<'_, G> {
+    type Item = Vec<G::Node>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(distance) = self.distance {
+            let mut next = Vec::new();
+            for node in &self.frontier {
+                for neighbor in self.graph.neighbors(node) {
+                    if !self.discovered.contains_key(&neighbor) {
+                        self.discovered
+                            .insert(neighbor.clone(), (distance + 1, Some(node.clone())));
+                        next.push(neighbor);
+                    }
+                }
+            }
+            self.frontier = next;
+        }
 
-pub fn floodfill<N: Node>(
-    graph: &impl UnweightedGraph<Node = N>,
-    start: impl Into<N>) -> HashSet<N> {
-    bfs_impl(graph, start, None).visited
+        if self.frontier.is_empty() {
+            return None;
+        }
+        self.distance = Some(self.distance.map_or(0, |distance| distance + 1));
+        Some(self.frontier.clone())
+    }
 }
 
-fn bfs_impl<N: Node>(
-    graph: &impl UnweightedGraph<Node = N>,
-    start: impl Into<N>,
-    end: Option<N>,
-) -> BfsResult<N> {
-    let start = start.into();
+/// Result of `bfs`, with all nodes up to and including the layer of the target
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BfsResult<N: Node> {
+    target: Option<N>,
+    discovered: HashMap<N, (usize, Option<N>)>,
+    layers: Vec<Vec<N>>,
+}
 
-    let mut distance = 0;
-    let mut visited: HashSet<N> = HashSet::new();
+impl<N: Node> BfsResult<N> {
+    /// The first node that satisfied the target predicate
+    pub fn target(&self) -> Option<&N> {
+        self.target.as_ref()
+    }
+
+    /// Distance to the target
+    pub fn distance(&self) -> Option<usize> {
+        self.target.as_ref().and_then(|target| self.distance_to(target))
+    }
+
+    pub fn distance_to(&self, node: &N) -> Option<usize> {
+        self.discovered.get(node).map(|&(distance, _)| distance)
+    }
 
-    let mut next = HashSet::new();
-    next.insert(start);
+    /// The node from which `node` was discovered, None for start nodes and unvisited nodes
+    pub fn parent(&self, node: &N) -> Option<&N> {
+        self.discovered.get(node)?.1.as_ref()
+    }
 
-    loop {
-        if next.is_empty() {
-            return BfsResult { distance: None, visited };
+    /// A shortest path from a start node to `node`, including both
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        self.discovered.get(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(parent) = self.parent(path.last().unwrap()) {
+            path.push(parent.clone());
         }
-        let mut neighbors = HashSet::new();
-        for node in next {
-            visited.insert(node.clone());
+        path.reverse();
+        Some(path)
+    }
 
-            if Some(&node) == end.as_ref() {
-                return BfsResult { distance: Some(distance), visited };
-            }
+    /// A shortest path to the target
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.target.as_ref()?)
+    }
 
-            for n in graph.neighbors(&node) {
-                if !visited.contains(&n) {
-                    neighbors.insert(n);
-                }
-            }
+    pub fn visited(&self) -> impl Iterator<Item = &N> {
+        self.discovered.keys()
+    }
+
+    pub fn was_visited(&self, node: &N) -> bool {
+        self.discovered.contains_key(node)
+    }
+
+    /// The nodes at distance 0, 1, 2, ...
+    pub fn layers(&self) -> &[Vec<N>] {
+        &self.layers
+    }
+}
+
+/// Searches layer by layer until a node satisfies `reached_target`, or until all reachable
+/// nodes have been visited
+pub fn bfs<G: UnweightedGraph, S: Into<G::Node>>(
+    graph: &G,
+    start_nodes: impl IntoIterator<Item = S>,
+    reached_target: impl Fn(&G::Node) -> bool,
+) -> BfsResult<G::Node> {
+    let mut layers_iter = BfsLayers::new(graph, start_nodes);
+    let mut layers = Vec::new();
+    let mut target = None;
+    for layer in layers_iter.by_ref() {
+        target = layer.iter().find(|node| reached_target(node)).cloned();
+        layers.push(layer);
+        if target.is_some() {
+            break;
         }
-        next = neighbors;
-        distance += 1;
     }
+    BfsResult { target, discovered: layers_iter.discovered, layers }
+}
+
+pub fn floodfill<N: Node>(
+    graph: &impl UnweightedGraph<Node = N>,
+    start: impl Into<N>,
+) -> HashSet<N> {
+    BfsLayers::new(graph, [start]).flatten().collect()
 }
 
 pub trait WeightedGraph {
@@ -82,39 +169,217 @@ pub trait WeightedGraph {
     ) -> impl Iterator<Item = (Self::Node, Cost)> + 'a;
 }
 
+/// A WeightedGraph whose nodes can be numbered 0..num_nodes, so that searches can keep their
+/// state in flat Vecs (see `DenseNodeMap`) instead of HashMaps
+pub trait IndexedGraph: WeightedGraph {
+    fn num_nodes(&self) -> usize;
+    fn index(&self, node: &Self::Node) -> usize;
+}
+
+/// What dijkstra and astar know about a node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeInfo<N> {
+    /// Final once settled, tentative before that
+    distance: Cost,
+    settled: bool,
+    /// Stored separately from the others so that nodes without ties don't allocate
+    first_predecessor: Option<N>,
+    more_predecessors: Vec<N>,
+}
+
+impl<N> NodeInfo<N> {
+    fn new(distance: Cost, predecessor: Option<N>) -> Self {
+        Self {
+            distance,
+            settled: false,
+            first_predecessor: predecessor,
+            more_predecessors: Vec::new(),
+        }
+    }
+
+    fn predecessors(&self) -> impl Iterator<Item = &N> {
+        self.first_predecessor.iter().chain(&self.more_predecessors)
+    }
+}
+
+/// Result of `dijkstra` and `astar`. Only nodes that were settled before the search stopped
+/// have a distance, so search with `|_| false` as target to get the distances to all
+/// reachable nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DijkstraResult<N: Node, M = HashMap<N, NodeInfo<N>>> {
+    target: Option<N>,
+    nodes: M,
+}
+
+impl<N: Node, M: NodeMap<N, NodeInfo<N>>> DijkstraResult<N, M> {
+    /// The first node that satisfied the target predicate
+    pub fn target(&self) -> Option<&N> {
+        self.target.as_ref()
+    }
+
+    /// Distance to the target
+    pub fn distance(&self) -> Option<Cost> {
+        self.target.as_ref().and_then(|target| self.distance_to(target))
+    }
+
+    fn settled(&self, node: &N) -> Option<&NodeInfo<N>> {
+        self.nodes.get(node).filter(|info| info.settled)
+    }
+
+    pub fn distance_to(&self, node: &N) -> Option<Cost> {
+        self.settled(node).map(|info| info.distance)
+    }
+
+    /// All settled nodes with their distance
+    pub fn distances(&self) -> impl Iterator<Item = (&N, Cost)> {
+        self.nodes.iter().filter(|(_, info)| info.settled).map(|(node, info)| (node, info.distance))
+    }
+
+    /// All nodes that directly precede `node` on some shortest path to it, i.e. its edges in the
+    /// shortest path DAG. Complete as long as all edge costs are positive, also for astar: it
+    /// settles all nodes with the same estimate as the target before it stops.
+    pub fn predecessors(&self, node: &N) -> impl Iterator<Item = &N> {
+        self.settled(node).into_iter().flat_map(NodeInfo::predecessors)
+    }
+
+    /// One shortest path from a start node to `node`, including both
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let mut info = self.settled(node)?;
+        let mut path = vec![node.clone()];
+        while let Some(predecessor) = &info.first_predecessor {
+            path.push(predecessor.clone());
+            info = self.nodes.get(predecessor).unwrap();
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// One shortest path to the target
+    pub fn path(&self) -> Option<Vec<N>> {
+        self.path_to(self.target.as_ref()?)
+    }
+
+    /// Every node that lies on any shortest path to `node`, including `node` and the start
+    pub fn nodes_on_shortest_paths_to(&self, node: &N) -> HashSet<N> {
+        let mut nodes = HashSet::new();
+        if self.settled(node).is_none() {
+            return nodes;
+        }
+
+        let mut stack = vec![node.clone()];
+        while let Some(current) = stack.pop() {
+            if nodes.insert(current.clone()) {
+                stack.extend(self.predecessors(&current).cloned());
+            }
+        }
+        nodes
+    }
+}
+
 pub fn dijkstra<T: Node, S: Into<T>>(
     graph: &impl WeightedGraph<Node = T>,
     start_nodes: impl IntoIterator<Item = S>,
     reached_target: impl Fn(&T) -> bool,
-) -> Option<usize> {
-    let mut visited = HashSet::new();
-    let mut queue = PriorityQueue::new();
+) -> DijkstraResult<T> {
+    astar(graph, start_nodes, reached_target, |_| 0)
+}
+
+/// dijkstra with flat Vecs instead of HashMaps
+pub fn dijkstra_indexed<'a, G: IndexedGraph, S: Into<G::Node>>(
+    graph: &'a G,
+    start_nodes: impl IntoIterator<Item = S>,
+    reached_target: impl Fn(&G::Node) -> bool,
+) -> DijkstraResult<G::Node, DenseNodeMap<'a, G, NodeInfo<G::Node>>> {
+    astar_with(
+        graph,
+        start_nodes,
+        reached_target,
+        |_| 0,
+        DenseNodeMap::new(graph),
+        HeapQueue::new(),
+    )
+}
+
+/// Like dijkstra, but explores nodes in the order of their distance plus `heuristic`, which
+/// estimates the remaining cost to a target. The heuristic must never overestimate, and must
+/// not drop by more than the cost of an edge, otherwise the distances aren't the shortest ones.
+pub fn astar<T: Node, S: Into<T>>(
+    graph: &impl WeightedGraph<Node = T>,
+    start_nodes: impl IntoIterator<Item = S>,
+    reached_target: impl Fn(&T) -> bool,
+    heuristic: impl Fn(&T) -> Cost,
+) -> DijkstraResult<T> {
+    astar_with(graph, start_nodes, reached_target, heuristic, HashMap::new(), HeapQueue::new())
+}
+
+/// astar with a custom node map and queue, e.g. a `DenseNodeMap` for an IndexedGraph or a
+/// `BucketQueue` for small integer costs. Nodes are pushed again when their distance improves,
+/// outdated entries are skipped when they are popped.
+pub fn astar_with<T: Node, S: Into<T>, M: NodeMap<T, NodeInfo<T>>>(
+    graph: &impl WeightedGraph<Node = T>,
+    start_nodes: impl IntoIterator<Item = S>,
+    reached_target: impl Fn(&T) -> bool,
+    heuristic: impl Fn(&T) -> Cost,
+    mut nodes: M,
+    mut queue: impl MinQueue<T>,
+) -> DijkstraResult<T, M> {
     for start_node in start_nodes.into_iter() {
-        queue.push(start_node.into(), std::cmp::Reverse(0));
+        let start_node = start_node.into();
+        queue.push(heuristic(&start_node), start_node.clone());
+        nodes.insert(start_node, NodeInfo::new(0, None));
     }
 
-    loop {
-        match queue.pop() {
-            None => return None,
-            Some((node, Reverse(current_cost))) => {
-                if reached_target(&node) {
-                    return Some(current_cost);
+    let mut target = None;
+    while let Some((priority, node)) = queue.pop() {
+        // Nodes with the same estimate as the target can still be predecessors of it
+        if target.as_ref().is_some_and(|&(_, target_priority)| priority > target_priority) {
+            break;
+        }
+
+        let info = nodes.get_mut(&node).unwrap();
+        if info.settled {
+            continue;
+        }
+        info.settled = true;
+        let current_cost = info.distance;
+
+        if target.is_none() && reached_target(&node) {
+            target = Some((node.clone(), priority));
+        }
+
+        for (neighbor, cost) in graph.neighbors(&node) {
+            let new_cost = current_cost + cost;
+            match nodes.get_mut(&neighbor) {
+                // With a heuristic, the neighbor may have been settled before this equally
+                // good predecessor
+                Some(info) if new_cost == info.distance => {
+                    info.more_predecessors.push(node.clone());
                 }
-                visited.insert(node.clone());
-
-                for (neighbor, cost) in graph.neighbors(&node) {
-                    if !visited.contains(&neighbor) && queue.get(&neighbor).is_none() {
-                        queue.push(neighbor, Reverse(cost + current_cost));
-                    } else if let Some(Reverse(previous_cost)) = queue.get_priority(&neighbor) {
-                        let new_cost = current_cost + cost;
-                        if new_cost < *previous_cost {
-                            queue.change_priority(&neighbor, Reverse(new_cost));
-                        }
-                    }
+                Some(info) if info.settled || new_cost > info.distance => {}
+                Some(info) => {
+                    *info = NodeInfo::new(new_cost, Some(node.clone()));
+                    queue.push(new_cost + heuristic(&neighbor), neighbor);
+                }
+                None => {
+                    queue.push(new_cost + heuristic(&neighbor), neighbor.clone());
+                    nodes.insert(neighbor, NodeInfo::new(new_cost, Some(node.clone())));
                 }
             }
         }
     }
+
+    DijkstraResult { target: target.map(|(target, _)| target), nodes }
+}
+
+/// Heuristic for `astar` on graphs whose nodes have a position, e.g. grids where every step
+/// costs at least `min_step_cost`
+pub fn manhattan_heuristic<T>(
+    target: impl Into<Vec2D>,
+    min_step_cost: Cost,
+    position: impl Fn(&T) -> Vec2D,
+) -> impl Fn(&T) -> Cost {
+    let target = target.into();
+    move |node| (position(node) - target).manhattan_dist() as Cost * min_step_cost
 }
 
 pub enum NextState<I> {
@@ -158,14 +423,13 @@ fn dfs_impl<T>(
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-
     use crate::{
         grid::{self, Grid},
         math::Vec2D,
     };
 
     use super::*;
+    use itertools::Itertools;
     use pretty_assertions::assert_eq;
 
     #[derive(Debug, Clone)]
@@ -194,11 +458,79 @@ mod tests {
         edges.insert("F", vec![]);
         let graph = SimpleWeightedGraph { edges };
 
-        assert_eq!(dijkstra(&graph, ["A"], |&node| node == "B"), Some(1));
-        assert_eq!(dijkstra(&graph, ["A"], |&node| node == "D"), Some(2));
-        assert_eq!(dijkstra(&graph, ["A"], |&node| node == "C"), Some(5));
-        assert_eq!(dijkstra(&graph, ["A"], |&node| node == "F"), Some(6));
-        assert_eq!(dijkstra(&graph, ["A"], |&node| node == "G"), None);
+        let distance = |target| dijkstra(&graph, ["A"], |&node| node == target).distance();
+        assert_eq!(distance("B"), Some(1));
+        assert_eq!(distance("D"), Some(2));
+        assert_eq!(distance("C"), Some(5));
+        assert_eq!(distance("F"), Some(6));
+        assert_eq!(distance("G"), None);
+
+        let result = dijkstra(&graph, ["A"], |&node| node == "F");
+        assert_eq!(result.target(), Some(&"F"));
+        assert_eq!(result.path(), Some(vec!["A", "D", "C", "F"]));
+        assert_eq!(result.path_to(&"B"), Some(vec!["A", "B"]));
+        assert_eq!(result.predecessors(&"A").count(), 0);
+
+        let all = dijkstra(&graph, ["A"], |_| false);
+        assert_eq!(all.target(), None);
+        assert_eq!(all.distance(), None);
+        assert_eq!(all.distances().count(), 5);
+        assert_eq!(all.distance_to(&"F"), Some(6));
+        assert_eq!(all.path_to(&"G"), None);
+    }
+
+    #[test]
+    fn all_shortest_paths() {
+        // Two shortest paths from A to D, and a longer one
+        let mut edges = HashMap::new();
+        edges.insert("A", vec![("B", 1), ("C", 2), ("E", 1)]);
+        edges.insert("B", vec![("D", 2)]);
+        edges.insert("C", vec![("D", 1)]);
+        edges.insert("E", vec![("D", 5)]);
+        edges.insert("D", vec![]);
+        let graph = SimpleWeightedGraph { edges };
+
+        let result = dijkstra(&graph, ["A"], |&node| node == "D");
+        assert_eq!(result.distance(), Some(3));
+        let mut predecessors = result.predecessors(&"D").copied().collect_vec();
+        predecessors.sort();
+        assert_eq!(predecessors, vec!["B", "C"]);
+        assert_eq!(
+            result.nodes_on_shortest_paths_to(&"D"),
+            ["A", "B", "C", "D"].into_iter().collect()
+        );
+        assert_eq!(result.path().unwrap().len(), 3);
+
+        // Multiple starts: paths begin at whichever start is closer
+        let result = dijkstra(&graph, ["A", "C"], |&node| node == "D");
+        assert_eq!(result.path(), Some(vec!["C", "D"]));
+    }
+
+    #[test]
+    fn astar_all_shortest_paths() {
+        // S - B - A and S - C - A, with the heuristic counting the remaining edges. All nodes
+        // have the same estimate, and the target A is popped before C.
+        let mut edges = HashMap::new();
+        edges.insert("S", vec![("B", 1), ("C", 1)]);
+        edges.insert("B", vec![("A", 1)]);
+        edges.insert("C", vec![("A", 1)]);
+        edges.insert("A", vec![]);
+        let graph = SimpleWeightedGraph { edges };
+        let heuristic = |node: &&str| match *node {
+            "S" => 2,
+            "A" => 0,
+            _ => 1,
+        };
+
+        let result = astar(&graph, ["S"], |&node| node == "A", heuristic);
+        assert_eq!(result.distance(), Some(2));
+        let mut predecessors = result.predecessors(&"A").copied().collect_vec();
+        predecessors.sort();
+        assert_eq!(predecessors, vec!["B", "C"]);
+        assert_eq!(
+            result.nodes_on_shortest_paths_to(&"A"),
+            ["S", "B", "C", "A"].into_iter().collect()
+        );
     }
 
     #[test]
@@ -224,9 +556,128 @@ mod tests {
         ]
         .into();
 
-        assert_eq!(dijkstra(&grid, [Vec2D::new(0, 0)], |&node| node == Vec2D::new(5, 3)), Some(11));
-        assert_eq!(dijkstra(&grid, [Vec2D::new(0, 0)], |&node| node == Vec2D::new(-1, -1)), None);
-        assert_eq!(dijkstra(&grid, [Vec2D::new(0, 0)], |&node| node == Vec2D::new(0, 0)), Some(0));
+        let distance = |target| dijkstra(&grid, [Vec2D::new(0, 0)], |&node| node == target);
+        assert_eq!(distance(Vec2D::new(5, 3)).distance(), Some(11));
+        assert_eq!(distance(Vec2D::new(-1, -1)).distance(), None);
+        assert_eq!(distance(Vec2D::new(0, 0)).distance(), Some(0));
+        assert_eq!(distance(Vec2D::new(0, 0)).path(), Some(vec![Vec2D::new(0, 0)]));
+
+        let path = distance(Vec2D::new(5, 3)).path().unwrap();
+        assert_eq!(path.first(), Some(&Vec2D::new(0, 0)));
+        assert_eq!(path.last(), Some(&Vec2D::new(5, 3)));
+        let cost = path
+            .iter()
+            .tuple_windows()
+            .map(|(&a, &b)| grid::WeightedGrid::cost(&grid, a, b))
+            .sum::<Cost>();
+        assert_eq!(cost, 11);
+    }
+
+    /// Entering a cell costs its value
+    struct Costs(Grid<usize>);
+
+    impl WeightedGraph for Costs {
+        type Node = Vec2D;
+
+        fn neighbors<'a, 'b: 'a>(
+            &'a self,
+            node: &'b Self::Node,
+        ) -> impl Iterator<Item = (Self::Node, Cost)> + 'a {
+            self.0.orthogonal_neighbors(node).map(|pos| (pos, self.0[pos]))
+        }
+    }
+
+    impl IndexedGraph for Costs {
+        fn num_nodes(&self) -> usize {
+            self.0.num_rows() * self.0.num_cols()
+        }
+
+        fn index(&self, node: &Vec2D) -> usize {
+            node.y as usize * self.0.num_cols() + node.x as usize
+        }
+    }
+
+    fn costs() -> Costs {
+        Costs(
+            vec![
+                vec![1, 3, 1, 1, 1, 9],
+                vec![1, 9, 9, 9, 1, 9],
+                vec![1, 1, 2, 9, 1, 1],
+                vec![9, 9, 1, 1, 7, 1],
+            ]
+            .into(),
+        )
+    }
+
+    #[test]
+    fn astar_matches_dijkstra() {
+        let costs = costs();
+
+        for target in costs.0.coordinates_row_major() {
+            let heuristic = manhattan_heuristic(target, 1, |&pos: &Vec2D| pos);
+            let expected = dijkstra(&costs, [Vec2D::new(0, 0)], |&pos| pos == target);
+            let result = astar(&costs, [Vec2D::new(0, 0)], |&pos| pos == target, heuristic);
+            assert_eq!(result.distance(), expected.distance(), "distance to {target:?}");
+            assert!(result.distances().count() <= expected.distances().count());
+            assert_eq!(
+                result.nodes_on_shortest_paths_to(&target),
+                expected.nodes_on_shortest_paths_to(&target)
+            );
+
+            let path = result.path().unwrap();
+            assert_eq!(
+                path.iter().skip(1).map(|&pos| costs.0[pos]).sum::<Cost>(),
+                result.distance().unwrap()
+            );
+        }
+
+        let far = astar(
+            &costs,
+            [Vec2D::new(0, 0)],
+            |&pos| pos == (5, 3),
+            manhattan_heuristic((5, 3), 1, |&pos: &Vec2D| pos),
+        );
+        assert_eq!(far.distance(), Some(10));
+        assert_eq!(astar(&costs, [Vec2D::new(0, 0)], |_| false, |_| 0).distances().count(), 24);
+    }
+
+    #[test]
+    fn indexed_and_bucket_queue() {
+        let costs = costs();
+        let start = [Vec2D::new(0, 0)];
+        let expected = dijkstra(&costs, start, |_| false);
+        let indexed = dijkstra_indexed(&costs, start, |_| false);
+        let bucket = astar_with(
+            &costs,
+            start,
+            |_| false,
+            |_| 0,
+            DenseNodeMap::new(&costs),
+            BucketQueue::new(),
+        );
+        for (node, distance) in expected.distances() {
+            assert_eq!(indexed.distance_to(node), Some(distance));
+            assert_eq!(bucket.distance_to(node), Some(distance));
+            assert_eq!(
+                indexed.predecessors(node).collect::<HashSet<_>>(),
+                expected.predecessors(node).collect::<HashSet<_>>()
+            );
+        }
+        assert_eq!(indexed.distances().count(), 24);
+        assert_eq!(bucket.distances().count(), 24);
+
+        // Dial's queue also works for astar, since the Manhattan heuristic is consistent
+        let target = Vec2D::new(5, 3);
+        let heuristic = manhattan_heuristic(target, 1, |&pos: &Vec2D| pos);
+        let result = astar_with(
+            &costs,
+            start,
+            |&pos| pos == target,
+            heuristic,
+            DenseNodeMap::new(&costs),
+            BucketQueue::new(),
+        );
+        assert_eq!(result.distance(), Some(10));
     }
 
     #[test]
@@ -248,20 +699,57 @@ mod tests {
         ]
         .into();
 
-        assert_eq!(bfs(&grid, (0, 0), (6, 3)).distance, Some(11));
-        assert_eq!(bfs(&grid, (0, 0), (2, 2)).distance, None);
-        assert_eq!(bfs(&grid, (0, 0), (0, 0)).distance, Some(0));
+        let to = |target: (i64, i64)| bfs(&grid, [(0, 0)], |&node| node == target);
+        assert_eq!(to((6, 3)).distance(), Some(11));
+        assert_eq!(to((2, 2)).distance(), None);
+        assert_eq!(to((0, 0)).distance(), Some(0));
+        assert_eq!(to((0, 0)).path(), Some(vec![Vec2D::new(0, 0)]));
 
+        let result = to((2, 3));
+        assert_eq!(result.distance(), Some(4));
         assert_eq!(
-            bfs(&grid, (0, 0), (2, 3)),
-            BfsResult {
-                distance: Some(4),
-                visited: [(0, 0), (0, 1), (1, 1), (0, 2), (0, 3), (1, 3), (2, 3)]
-                    .into_iter()
-                    .map(Vec2D::from)
-                    .collect(),
-            }
+            result.visited().copied().collect::<HashSet<_>>(),
+            [(0, 0), (0, 1), (1, 1), (0, 2), (0, 3), (1, 3), (2, 3)]
+                .into_iter()
+                .map(Vec2D::from)
+                .collect(),
+        );
+        assert_eq!(
+            result.path(),
+            Some([(0, 0), (0, 1), (0, 2), (1, 3), (2, 3)].into_iter().map(Vec2D::from).collect())
         );
+        assert_eq!(result.parent(&Vec2D::new(2, 3)), Some(&Vec2D::new(1, 3)));
+        assert_eq!(result.parent(&Vec2D::new(0, 0)), None);
+        assert_eq!(result.layers().iter().map(Vec::len).collect_vec(), vec![1, 2, 1, 2, 1]);
+        assert!(!result.was_visited(&Vec2D::new(3, 0)));
+    }
+
+    #[test]
+    fn bfs_layers() {
+        let grid: Grid<char> = vec![
+            vec!['.', '.', '.', '#', '.'],
+            vec!['.', '#', '.', '#', '.'],
+            vec!['.', '.', '.', '.', '.'],
+        ]
+        .into();
+
+        // Multiple starts, all at distance 0
+        let layers = BfsLayers::new(&grid, [(0, 0), (4, 0)]).collect_vec();
+        assert_eq!(layers[0], vec![Vec2D::new(0, 0), Vec2D::new(4, 0)]);
+        assert_eq!(layers.iter().map(Vec::len).sum::<usize>(), 12);
+        assert_eq!(layers.len(), 4);
+
+        let result = bfs(&grid, [(0, 0), (4, 0)], |&node| node == (2, 0));
+        assert_eq!(result.distance(), Some(2));
+        assert_eq!(result.path().unwrap().first(), Some(&Vec2D::new(0, 0)));
+        assert_eq!(result.distance_to(&Vec2D::new(4, 2)), Some(2));
+
+        // Without a target, everything reachable is visited
+        let all = bfs(&grid, [(0, 0)], |_| false);
+        assert_eq!(all.target(), None);
+        assert_eq!(all.visited().count(), 12);
+        assert_eq!(all.distance_to(&Vec2D::new(4, 0)), Some(5));
+        assert_eq!(floodfill(&grid, (4, 0)).len(), 12);
     }
 
     #[test]
@@ -0,0 +1,214 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{Cost, Node, UnweightedGraph, WeightedGraph};
+
+/// The junctions of a graph, connected by edges whose cost is the number of steps through the
+/// corridor between them. Built by `compress`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedGraph<N: Node> {
+    edges: HashMap<N, Vec<(N, Cost)>>,
+}
+
+impl<N: Node> CompressedGraph<N> {
+    pub fn junctions(&self) -> impl Iterator<Item = &N> {
+        self.edges.keys()
+    }
+
+    pub fn num_junctions(&self) -> usize {
+        self.edges.len()
+    }
+
+    pub fn contains(&self, node: &N) -> bool {
+        self.edges.contains_key(node)
+    }
+
+    /// Outgoing edges of a junction. There can be several edges to the same junction if
+    /// multiple corridors connect them.
+    pub fn edges(&self, junction: &N) -> &[(N, Cost)] {
+        self.edges.get(junction).map_or(&[], Vec::as_slice)
+    }
+}
+
+impl<N: Node> WeightedGraph for CompressedGraph<N> {
+    type Node = N;
+
+    fn neighbors<'a, 'b: 'a>(
+        &'a self,
+        node: &'b Self::Node,
+    ) -> impl Iterator<Item = (Self::Node, Cost)> + 'a {
+        self.edges(node).iter().cloned()
+    }
+}
+
+/// Contracts the corridors of a graph, i.e. the chains of nodes that aren't junctions, into
+/// single weighted edges between junctions. The start nodes are always junctions, and only
+/// junctions reachable from them are included.
+///
+/// Corridors are walked without turning back, so in an undirected graph a corridor node
+/// should have two neighbors. A corridor that ends without reaching a junction is dropped, as
+/// is one that branches even though `is_junction` said it doesn't. For directed graphs (e.g.
+/// slopes that can only be walked downhill) this means edges only go where the graph allows,
+/// and corridors that end in a loop without a junction are dropped as well.
+pub fn compress<G: UnweightedGraph, S: Into<G::Node>>(
+    graph: &G,
+    start_nodes: impl IntoIterator<Item = S>,
+    is_junction: impl Fn(&G::Node) -> bool,
+) -> CompressedGraph<G::Node> {
+    let mut edges = HashMap::new();
+    let mut todo = start_nodes.into_iter().map(Into::into).collect::<Vec<_>>();
+    let starts = todo.clone();
+    let is_junction = |node: &G::Node| starts.contains(node) || is_junction(node);
+    let mut corridor = HashSet::new();
+
+    while let Some(junction) = todo.pop() {
+        if edges.contains_key(&junction) {
+            continue;
+        }
+
+        let mut junction_edges = Vec::new();
+        for first_step in graph.neighbors(&junction) {
+            let (mut previous, mut current, mut length) = (junction.clone(), first_step, 1);
+            corridor.clear();
+            let end = loop {
+                if is_junction(&current) {
+                    break Some(current);
+                }
+                // A directed corridor can run into a loop without junctions
+                if !corridor.insert(current.clone()) {
+                    break None;
+                }
+                let onward = {
+                    let mut onward = graph.neighbors(&current).filter(|next| *next != previous);
+                    (onward.next(), onward.next())
+                };
+                match onward {
+                    (Some(next), None) => {
+                        previous = std::mem::replace(&mut current, next);
+                        length += 1;
+                    }
+                    _ => break None,
+                }
+            };
+
+            if let Some(end) = end {
+                todo.push(end.clone());
+                junction_edges.push((end, length));
+            }
+        }
+        edges.insert(junction, junction_edges);
+    }
+
+    CompressedGraph { edges }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        graphs::{bfs, dijkstra},
+        grid::Grid,
+        math::Vec2D,
+    };
+
+    use super::*;
+
+    /// Open cells are '.', and '>' can only be entered and left towards the east
+    struct Maze(Grid<char>);
+
+    impl UnweightedGraph for Maze {
+        type Node = Vec2D;
+
+        fn neighbors<'a, 'b: 'a>(&'a self, node: &'b Vec2D) -> impl Iterator<Item = Vec2D> + 'a {
+            node.orthogonal_neighbors().filter(move |next| match self.0.get(*next) {
+                Some('.') => self.0[*node] != '>' || *next - *node == (1, 0),
+                Some('>') => *next - *node == (1, 0),
+                _ => false,
+            })
+        }
+    }
+
+    /// Directed edges between chars
+    struct Arrows(&'static [(char, char)]);
+
+    impl UnweightedGraph for Arrows {
+        type Node = char;
+
+        fn neighbors<'a, 'b: 'a>(&'a self, node: &'b char) -> impl Iterator<Item = char> + 'a {
+            self.0.iter().filter(move |(from, _)| from == node).map(|&(_, to)| to)
+        }
+    }
+
+    fn maze(rows: &[&str]) -> Maze {
+        Maze(rows.iter().map(|row| row.chars().collect::<Vec<_>>()).collect::<Vec<_>>().into())
+    }
+
+    fn is_junction(maze: &Maze) -> impl Fn(&Vec2D) -> bool + '_ {
+        |pos| {
+            pos.orthogonal_neighbors().filter(|n| maze.0.get(*n).is_some_and(|&c| c != '#')).count()
+                > 2
+        }
+    }
+
+    #[test]
+    fn undirected() {
+        // Two ways from (2, 1) to the junctions at the bottom, which end in dead ends
+        let maze = maze(&[
+            "##.####", //
+            "#.....#", //
+            "#.###.#", //
+            "#.###.#", //
+            "#.#.#.#", //
+            "#.....#", //
+            "#.##.##", //
+            "#.##.##", //
+        ]);
+        let compressed = compress(&maze, [(2, 0)], is_junction(&maze));
+
+        let junctions = [(2, 0), (2, 1), (1, 5), (3, 5), (4, 5)].map(Vec2D::from);
+        assert_eq!(compressed.num_junctions(), junctions.len());
+        assert!(junctions.iter().all(|junction| compressed.contains(junction)));
+        assert_eq!(compressed.edges(&Vec2D::new(2, 0)), &[(Vec2D::new(2, 1), 1)]);
+
+        let mut edges = compressed.edges(&Vec2D::new(2, 1)).to_vec();
+        edges.sort();
+        assert_eq!(
+            edges,
+            vec![(Vec2D::new(1, 5), 5), (Vec2D::new(2, 0), 1), (Vec2D::new(4, 5), 8)]
+        );
+
+        // Distances agree with the uncompressed maze
+        let uncompressed = bfs(&maze, [(2, 0)], |_| false);
+        let compressed = dijkstra(&compressed, [(2, 0)], |_| false);
+        for junction in junctions {
+            assert_eq!(compressed.distance_to(&junction), uncompressed.distance_to(&junction));
+        }
+    }
+
+    #[test]
+    fn directed() {
+        let maze = maze(&[
+            "#.#####", //
+            "#...>.#", //
+            "#.###.#", //
+            "#.....#", //
+            "#####.#", //
+        ]);
+        let compressed = compress(&maze, [(1, 0)], is_junction(&maze));
+        let (top_left, bottom_right) = (Vec2D::new(1, 1), Vec2D::new(5, 3));
+
+        // The upper corridor can only be walked east, the lower one both ways
+        let mut edges = compressed.edges(&top_left).to_vec();
+        edges.sort();
+        assert_eq!(edges, vec![(Vec2D::new(1, 0), 1), (bottom_right, 6), (bottom_right, 6)]);
+        assert_eq!(
+            compressed.edges(&bottom_right).iter().filter(|(end, _)| *end == top_left).count(),
+            1
+        );
+
+        // J -> A -> B -> C -> A never gets to a junction again, J -> D -> K does
+        let arrows =
+            Arrows(&[('J', 'A'), ('A', 'B'), ('B', 'C'), ('C', 'A'), ('J', 'D'), ('D', 'K')]);
+        let compressed = compress(&arrows, ['J'], |&node| node == 'K');
+        assert_eq!(compressed.edges(&'J'), &[('K', 2)]);
+        assert_eq!(compressed.num_junctions(), 2);
+    }
+}
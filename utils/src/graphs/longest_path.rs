@@ -0,0 +1,208 @@
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use rayon::prelude::*;
+
+use super::{Cost, WeightedGraph};
+
+/// Length of the longest path from `start` to `end` that visits no node twice, or None if
+/// `end` can't be reached. This is NP-hard, so it's meant for small graphs like the output of
+/// `compress`: panics if more than 64 nodes are reachable from `start`.
+pub fn longest_simple_path<G: WeightedGraph, S: Into<G::Node>>(
+    graph: &G,
+    start: S,
+    end: S,
+) -> Option<Cost> {
+    LongestPathSearch::new(graph, start.into(), end.into(), 0).run()
+}
+
+/// longest_simple_path, but the branches of the first levels of the search are explored in
+/// parallel
+pub fn par_longest_simple_path<G: WeightedGraph, S: Into<G::Node>>(
+    graph: &G,
+    start: S,
+    end: S,
+) -> Option<Cost> {
+    LongestPathSearch::new(graph, start.into(), end.into(), PARALLEL_DEPTH).run()
+}
+
+/// Enough levels to keep all cores busy with the branching factor of typical junction graphs
+const PARALLEL_DEPTH: usize = 6;
+
+/// The reachable nodes, numbered so that a set of them fits into a u64
+struct LongestPathSearch {
+    /// Outgoing edges of each node, longest first so that long paths are found early
+    edges: Vec<Vec<(usize, Cost)>>,
+    /// The longest edge into each node, which bounds what visiting it can add to a path
+    max_incoming: Vec<Cost>,
+    start: usize,
+    end: Option<usize>,
+    parallel_depth: usize,
+    /// One more than the longest path found so far (0 if there is none yet), shared by all
+    /// branches for pruning
+    best: AtomicUsize,
+}
+
+impl LongestPathSearch {
+    fn new<G: WeightedGraph>(
+        graph: &G,
+        start: G::Node,
+        end: G::Node,
+        parallel_depth: usize,
+    ) -> Self {
+        let mut indices = HashMap::from([(start.clone(), 0)]);
+        let mut nodes = vec![start];
+        let mut edges = Vec::new();
+        while let Some(node) = nodes.get(edges.len()).cloned() {
+            let mut node_edges: Vec<(usize, Cost)> = Vec::new();
+            for (neighbor, cost) in graph.neighbors(&node) {
+                let index = *indices.entry(neighbor.clone()).or_insert_with(|| {
+                    nodes.push(neighbor);
+                    nodes.len() - 1
+                });
+                // Of several edges to the same node, only the longest matters
+                match node_edges.iter_mut().find(|(other, _)| *other == index) {
+                    Some((_, other_cost)) => *other_cost = cost.max(*other_cost),
+                    None => node_edges.push((index, cost)),
+                }
+            }
+            node_edges.sort_by_key(|&(_, cost)| std::cmp::Reverse(cost));
+            edges.push(node_edges);
+        }
+        assert!(nodes.len() <= 64, "{} nodes are reachable, at most 64 are supported", nodes.len());
+
+        let mut max_incoming = vec![0; nodes.len()];
+        for &(to, cost) in edges.iter().flatten() {
+            max_incoming[to] = max_incoming[to].max(cost);
+        }
+
+        Self {
+            edges,
+            max_incoming,
+            start: 0,
+            end: indices.get(&end).copied(),
+            parallel_depth,
+            best: AtomicUsize::new(0),
+        }
+    }
+
+    fn run(&self) -> Option<Cost> {
+        let end = self.end?;
+        let remaining = self.max_incoming.iter().sum::<Cost>() - self.max_incoming[self.start];
+        self.search(self.start, end, 1 << self.start, 0, remaining, 0)
+    }
+
+    /// `remaining` is the sum of `max_incoming` over the unvisited nodes, so no path can get
+    /// longer than `distance + remaining`
+    fn search(
+        &self,
+        node: usize,
+        end: usize,
+        visited: u64,
+        distance: Cost,
+        remaining: Cost,
+        depth: usize,
+    ) -> Option<Cost> {
+        if node == end {
+            self.best.fetch_max(distance + 1, Ordering::Relaxed);
+            return Some(distance);
+        }
+        if distance + remaining < self.best.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        let next = |&(neighbor, cost): &(usize, Cost)| {
+            self.search(
+                neighbor,
+                end,
+                visited | 1 << neighbor,
+                distance + cost,
+                remaining - self.max_incoming[neighbor],
+                depth + 1,
+            )
+        };
+        let unvisited =
+            self.edges[node].iter().filter(|(neighbor, _)| visited & 1 << neighbor == 0);
+        if depth < self.parallel_depth {
+            unvisited.collect::<Vec<_>>().into_par_iter().filter_map(next).max()
+        } else {
+            unvisited.filter_map(next).max()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    /// Undirected graph on the nodes 0..n
+    struct EdgeList(Vec<(usize, usize, Cost)>);
+
+    impl WeightedGraph for EdgeList {
+        type Node = usize;
+
+        fn neighbors<'a, 'b: 'a>(
+            &'a self,
+            node: &'b usize,
+        ) -> impl Iterator<Item = (usize, Cost)> + 'a {
+            self.0.iter().filter_map(move |&(a, b, cost)| match (a == *node, b == *node) {
+                (true, _) => Some((b, cost)),
+                (_, true) => Some((a, cost)),
+                _ => None,
+            })
+        }
+    }
+
+    /// Tries all orders of all subsets of the nodes in between
+    fn brute_force(graph: &EdgeList, num_nodes: usize, start: usize, end: usize) -> Option<Cost> {
+        let cost =
+            |a: usize, b: usize| graph.neighbors(&a).filter(|&(n, _)| n == b).map(|(_, c)| c).max();
+        let inner = (0..num_nodes).filter(|&n| n != start && n != end).collect_vec();
+        (0..=inner.len())
+            .flat_map(|k| inner.iter().copied().permutations(k))
+            .filter_map(|middle| {
+                std::iter::once(start)
+                    .chain(middle)
+                    .chain(std::iter::once(end))
+                    .tuple_windows()
+                    .map(|(a, b)| cost(a, b))
+                    .sum::<Option<Cost>>()
+            })
+            .max()
+    }
+
+    #[test]
+    fn small_graph() {
+        // 0 - 1 - 3 is direct, but the detour over 2 via the longer parallel edge is longer
+        let graph =
+            EdgeList(vec![(0, 1, 1), (1, 3, 1), (1, 2, 5), (2, 3, 2), (0, 2, 1), (0, 2, 3)]);
+        assert_eq!(longest_simple_path(&graph, 0usize, 3), Some(9));
+        assert_eq!(par_longest_simple_path(&graph, 0usize, 3), Some(9));
+        assert_eq!(longest_simple_path(&graph, 0usize, 0), Some(0));
+        assert_eq!(longest_simple_path(&graph, 0usize, 4), None);
+    }
+
+    #[test]
+    fn matches_brute_force() {
+        // Deterministic pseudo random edges on 8 nodes
+        let mut seed = 12345u64;
+        let mut random = |n: u64| {
+            seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            (seed >> 33) % n
+        };
+        for _ in 0..20 {
+            let edges = (0..12)
+                .map(|_| (random(8) as usize, random(8) as usize, random(10) as Cost + 1))
+                .filter(|(a, b, _)| a != b)
+                .collect();
+            let graph = EdgeList(edges);
+            let expected = brute_force(&graph, 8, 0, 7);
+            assert_eq!(longest_simple_path(&graph, 0usize, 7), expected);
+            assert_eq!(par_longest_simple_path(&graph, 0usize, 7), expected);
+        }
+    }
+}
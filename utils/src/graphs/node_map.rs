@@ -0,0 +1,73 @@
+use std::{collections::HashMap, hash::Hash};
+
+use super::IndexedGraph;
+
+/// Where the searches keep their state for each node
+pub trait NodeMap<N, V> {
+    fn get(&self, node: &N) -> Option<&V>;
+    fn get_mut(&mut self, node: &N) -> Option<&mut V>;
+    fn insert(&mut self, node: N, value: V);
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a N, &'a V)>
+    where
+        N: 'a,
+        V: 'a;
+}
+
+impl<N: Hash + Eq, V> NodeMap<N, V> for HashMap<N, V> {
+    fn get(&self, node: &N) -> Option<&V> {
+        HashMap::get(self, node)
+    }
+
+    fn get_mut(&mut self, node: &N) -> Option<&mut V> {
+        HashMap::get_mut(self, node)
+    }
+
+    fn insert(&mut self, node: N, value: V) {
+        HashMap::insert(self, node, value);
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a N, &'a V)>
+    where
+        N: 'a,
+        V: 'a,
+    {
+        HashMap::iter(self)
+    }
+}
+
+/// A flat Vec with one slot per node of an IndexedGraph, so no hashing is needed
+#[derive(Debug, Clone)]
+pub struct DenseNodeMap<'a, G: IndexedGraph, V> {
+    graph: &'a G,
+    slots: Vec<Option<(G::Node, V)>>,
+}
+
+impl<'a, G: IndexedGraph, V> DenseNodeMap<'a, G, V> {
+    pub fn new(graph: &'a G) -> Self {
+        Self { graph, slots: std::iter::repeat_with(|| None).take(graph.num_nodes()).collect() }
+    }
+}
+
+impl<G: IndexedGraph, V> NodeMap<G::Node, V> for DenseNodeMap<'_, G, V> {
+    fn get(&self, node: &G::Node) -> Option<&V> {
+        self.slots.get(self.graph.index(node))?.as_ref().map(|(_, value)| value)
+    }
+
+    fn get_mut(&mut self, node: &G::Node) -> Option<&mut V> {
+        self.slots.get_mut(self.graph.index(node))?.as_mut().map(|(_, value)| value)
+    }
+
+    /// Panics if the index of the node is out of range
+    fn insert(&mut self, node: G::Node, value: V) {
+        let index = self.graph.index(&node);
+        self.slots[index] = Some((node, value));
+    }
+
+    fn iter<'b>(&'b self) -> impl Iterator<Item = (&'b G::Node, &'b V)>
+    where
+        G::Node: 'b,
+        V: 'b,
+    {
+        self.slots.iter().flatten().map(|(node, value)| (node, value))
+    }
+}
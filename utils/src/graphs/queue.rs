@@ -0,0 +1,163 @@
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+};
+
+use super::Cost;
+
+/// Priority queue for the shortest path searches, popping the lowest priority first. Items
+/// are never updated in place, the searches push them again instead and skip outdated copies.
+pub trait MinQueue<T> {
+    fn push(&mut self, priority: Cost, item: T);
+    fn pop(&mut self) -> Option<(Cost, T)>;
+}
+
+/// Binary heap, works for any priorities
+#[derive(Debug, Clone)]
+pub struct HeapQueue<T>(BinaryHeap<Reverse<(Cost, T)>>);
+
+impl<T: Ord> HeapQueue<T> {
+    pub fn new() -> Self {
+        Self(BinaryHeap::new())
+    }
+}
+
+impl<T: Ord> Default for HeapQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Ord> MinQueue<T> for HeapQueue<T> {
+    fn push(&mut self, priority: Cost, item: T) {
+        self.0.push(Reverse((priority, item)));
+    }
+
+    fn pop(&mut self) -> Option<(Cost, T)> {
+        self.0.pop().map(|Reverse(entry)| entry)
+    }
+}
+
+/// Dial's bucket queue: one bucket per priority, in a ring that spans from the lowest to the
+/// highest queued priority. Push and pop are O(1) if edge costs are small integers, but
+/// priorities must never be lower than the last popped one. That holds for dijkstra, and for
+/// astar with a consistent heuristic.
+#[derive(Debug, Clone)]
+pub struct BucketQueue<T> {
+    buckets: VecDeque<Vec<T>>,
+    /// Priority of the front bucket
+    first: Cost,
+    last_popped: Cost,
+    len: usize,
+}
+
+impl<T> BucketQueue<T> {
+    pub fn new() -> Self {
+        Self { buckets: VecDeque::new(), first: 0, last_popped: 0, len: 0 }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<T> Default for BucketQueue<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> MinQueue<T> for BucketQueue<T> {
+    fn push(&mut self, priority: Cost, item: T) {
+        assert!(
+            priority >= self.last_popped,
+            "priority {priority} is lower than the last popped {}",
+            self.last_popped
+        );
+        if self.is_empty() {
+            // All buckets are empty, so they can start at any priority
+            self.first = priority;
+        } else if priority < self.first {
+            for _ in priority..self.first {
+                self.buckets.push_front(Vec::new());
+            }
+            self.first = priority;
+        }
+
+        let offset = priority - self.first;
+        if offset >= self.buckets.len() {
+            self.buckets.resize_with(offset + 1, Vec::new);
+        }
+        self.buckets[offset].push(item);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<(Cost, T)> {
+        if self.is_empty() {
+            return None;
+        }
+        loop {
+            if let Some(item) = self.buckets.front_mut()?.pop() {
+                self.len -= 1;
+                self.last_popped = self.first;
+                return Some((self.first, item));
+            }
+            // Move the empty bucket to the back, where it's reused for higher priorities
+            self.buckets.rotate_left(1);
+            self.first += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(queue: &mut impl MinQueue<char>) -> Vec<(Cost, char)> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    #[test]
+    fn heap_queue() {
+        let mut queue = HeapQueue::new();
+        for (priority, item) in [(5, 'a'), (1, 'b'), (3, 'c'), (1, 'd')] {
+            queue.push(priority, item);
+        }
+        assert_eq!(drain(&mut queue), vec![(1, 'b'), (1, 'd'), (3, 'c'), (5, 'a')]);
+    }
+
+    #[test]
+    fn bucket_queue() {
+        let mut queue = BucketQueue::new();
+        queue.push(12, 'b');
+        queue.push(10, 'a');
+        queue.push(10, 'c');
+        assert_eq!(queue.len(), 3);
+        assert_eq!(queue.pop().map(|(priority, _)| priority), Some(10));
+        assert_eq!(queue.pop().map(|(priority, _)| priority), Some(10));
+
+        // Monotone pushes while popping, wrapping around the ring a few times
+        queue.push(11, 'd');
+        queue.push(15, 'e');
+        assert_eq!(drain(&mut queue), vec![(11, 'd'), (12, 'b'), (15, 'e')]);
+        assert!(queue.is_empty());
+
+        // Pushing into an empty queue, lower than the previous push but not the last pop
+        queue.push(17, 'f');
+        queue.push(16, 'g');
+        assert_eq!(drain(&mut queue), vec![(16, 'g'), (17, 'f')]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bucket_queue_not_monotone() {
+        let mut queue = BucketQueue::new();
+        queue.push(5, 'a');
+        queue.pop();
+        queue.push(4, 'b');
+    }
+}
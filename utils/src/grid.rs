@@ -2,7 +2,13 @@ use std::ops::{Index, IndexMut};
 
 use itertools::Itertools;
 
-use crate::{graphs, math::Vec2D};
+use crate::{
+    graphs,
+    math::{Neighborhood, Vec2D},
+};
+
+mod bit_grid;
+pub use bit_grid::*;
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone)]
 pub struct Grid<T> {
@@ -170,22 +176,30 @@ impl<T> Grid<T> {
         self.coordinates_row_major().map(move |pos| (pos, &self[pos]))
     }
 
+    pub fn neighbors_in<'a>(
+        &'a self,
+        pos: &Vec2D,
+        neighborhood: &'a Neighborhood,
+    ) -> impl Iterator<Item = Vec2D> + 'a {
+        pos.neighbors_in(neighborhood).filter(move |neighbor| self.contains(neighbor))
+    }
+
     pub fn orthogonal_neighbors<'a, 'b: 'a>(
         &'a self,
         pos: &'b Vec2D,
     ) -> impl Iterator<Item = Vec2D> + 'a {
-        pos.orthogonal_neighbors().filter(move |neighbor| self.contains(neighbor))
+        self.neighbors_in(pos, &Neighborhood::VonNeumann)
     }
 
     pub fn diagonal_neighbors<'a, 'b: 'a>(
         &'a self,
         pos: &'b Vec2D,
     ) -> impl Iterator<Item = Vec2D> + 'a {
-        pos.diagonal_neighbors().filter(move |neighbor| self.contains(neighbor))
+        self.neighbors_in(pos, &Neighborhood::Diagonal)
     }
 
     pub fn all_neighbors<'a, 'b: 'a>(&'a self, pos: &'b Vec2D) -> impl Iterator<Item = Vec2D> + 'a {
-        pos.all_neighbors().filter(move |neighbor| self.contains(neighbor))
+        self.neighbors_in(pos, &Neighborhood::Moore)
     }
 }
 
@@ -278,6 +292,19 @@ impl<'a, T> DoubleEndedIterator for ColIter<'a, T> {
     }
 }
 
+/// Typed grid cell that can be parsed from and rendered to a single char, see Input::grid_of.
+/// Can be derived via aoc_derive::GridCell.
+pub trait GridCell: Sized + Default {
+    /// Chars (e.g. 'S' for the start) whose positions are extracted and which are replaced by
+    /// the default cell
+    const MARKERS: &'static [char] = &[];
+
+    fn from_char(c: char) -> Option<Self>;
+
+    /// Can be passed to Grid::render
+    fn to_char(&self) -> char;
+}
+
 pub trait UnweightedGrid {
     fn neighbors<'a, 'b: 'a>(&'a self, node: &'b Vec2D) -> impl Iterator<Item = Vec2D> + 'a;
 }
@@ -416,6 +443,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn neighbors_in() {
+        let grid = Grid::with_value(0, 3, 3);
+        assert_eq!(
+            grid.neighbors_in(&Vec2D::new(0, 0), &Neighborhood::knight_moves()).collect_vec(),
+            vec![Vec2D::new(1, 2), Vec2D::new(2, 1)]
+        );
+        assert_eq!(
+            grid.all_neighbors(&Vec2D::new(0, 0)).collect_vec(),
+            vec![Vec2D::new(1, 0), Vec2D::new(0, 1), Vec2D::new(1, 1)]
+        );
+        assert_eq!(grid.neighbors_in(&Vec2D::new(1, 1), &Neighborhood::HexAxial).count(), 6);
+        assert_eq!(grid.neighbors_in(&Vec2D::new(1, 1), &Neighborhood::Manhattan(2)).count(), 8);
+    }
+
     #[test]
     fn get_wrapping() {
         let grid: Grid<_> = [[1, 2, 3], [4, 5, 6]].into();
@@ -0,0 +1,377 @@
+use std::ops::{BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not};
+
+use itertools::Itertools;
+
+use crate::{graphs, math::Vec2D, render::Renderer};
+
+use super::{Grid, UnweightedGrid};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// Grid of booleans, stored row-wise as bits so that whole frontiers can be advanced with
+/// word-level operations. Bit x of row y is bit x % 64 of word x / 64 in that row.
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct BitGrid {
+    num_rows: usize,
+    num_cols: usize,
+    words_per_row: usize,
+    data: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(num_rows: usize, num_cols: usize) -> Self {
+        let words_per_row = num_cols.div_ceil(WORD_BITS);
+        Self { num_rows, num_cols, words_per_row, data: vec![0; num_rows * words_per_row] }
+    }
+
+    pub fn from_grid<T>(grid: &Grid<T>, predicate: impl Fn(&T) -> bool) -> Self {
+        let mut bit_grid = Self::new(grid.num_rows(), grid.num_cols());
+        for (pos, item) in grid.iter() {
+            if predicate(item) {
+                bit_grid.set(pos, true);
+            }
+        }
+        bit_grid
+    }
+
+    pub fn from_positions<P: Into<Vec2D>>(
+        num_rows: usize,
+        num_cols: usize,
+        positions: impl IntoIterator<Item = P>,
+    ) -> Self {
+        let mut bit_grid = Self::new(num_rows, num_cols);
+        for pos in positions {
+            bit_grid.set(pos, true);
+        }
+        bit_grid
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        (0..self.num_rows).map(|y| (0..self.num_cols).map(move |x| self.get((x, y)))).collect()
+    }
+
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    pub fn num_cols(&self) -> usize {
+        self.num_cols
+    }
+
+    pub fn contains(&self, pos: &Vec2D) -> bool {
+        pos.x >= 0 && pos.y >= 0 && pos.x < self.num_cols as i64 && pos.y < self.num_rows as i64
+    }
+
+    fn word_and_mask(&self, pos: Vec2D) -> (usize, u64) {
+        let (x, y) = (pos.x as usize, pos.y as usize);
+        (y * self.words_per_row + x / WORD_BITS, 1 << (x % WORD_BITS))
+    }
+
+    /// Positions outside of the grid are never set
+    pub fn get(&self, pos: impl Into<Vec2D>) -> bool {
+        let pos = pos.into();
+        if !self.contains(&pos) {
+            return false;
+        }
+        let (word, mask) = self.word_and_mask(pos);
+        self.data[word] & mask != 0
+    }
+
+    pub fn set(&mut self, pos: impl Into<Vec2D>, value: bool) {
+        let pos = pos.into();
+        assert!(self.contains(&pos), "{pos:?} is out of bounds");
+        let (word, mask) = self.word_and_mask(pos);
+        if value {
+            self.data[word] |= mask;
+        } else {
+            self.data[word] &= !mask;
+        }
+    }
+
+    pub fn row_words(&self, row: usize) -> &[u64] {
+        &self.data[row * self.words_per_row..(row + 1) * self.words_per_row]
+    }
+
+    pub fn count_ones(&self) -> usize {
+        self.data.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn row_count_ones(&self, row: usize) -> usize {
+        self.row_words(row).iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.iter().all(|&word| word == 0)
+    }
+
+    /// Positions of all set bits in row-major order
+    pub fn ones(&self) -> impl Iterator<Item = Vec2D> + '_ {
+        self.data.iter().enumerate().flat_map(move |(i, &word)| {
+            let (row, word_in_row) = (i / self.words_per_row, i % self.words_per_row);
+            (0..WORD_BITS)
+                .filter(move |bit| word & (1 << bit) != 0)
+                .map(move |bit| Vec2D::from((word_in_row * WORD_BITS + bit, row)))
+        })
+    }
+
+    /// Clears the unused bits in the last word of each row
+    fn mask_trailing_bits(&mut self) {
+        let used_bits = self.num_cols % WORD_BITS;
+        if used_bits == 0 {
+            return;
+        }
+        let mask = (1 << used_bits) - 1;
+        for row in 0..self.num_rows {
+            self.data[(row + 1) * self.words_per_row - 1] &= mask;
+        }
+    }
+
+    /// Moves every set bit by `offset`, bits moved outside of the grid are dropped
+    pub fn shifted(&self, offset: impl Into<Vec2D>) -> Self {
+        let offset = offset.into();
+        let mut shifted = Self::new(self.num_rows, self.num_cols);
+
+        let (word_shift, bit_shift) = (
+            offset.x.unsigned_abs() as usize / WORD_BITS,
+            offset.x.unsigned_abs() as usize % WORD_BITS,
+        );
+
+        for row in 0..self.num_rows {
+            let target_row = row as i64 + offset.y;
+            if target_row < 0 || target_row >= self.num_rows as i64 {
+                continue;
+            }
+            let source = self.row_words(row);
+            let target_start = target_row as usize * self.words_per_row;
+            let target = &mut shifted.data[target_start..target_start + self.words_per_row];
+
+            for (i, word) in target.iter_mut().enumerate() {
+                // Assemble the target word from the (up to) two source words it overlaps
+                let source_word = |index: Option<usize>| {
+                    index.and_then(|index| source.get(index).copied()).unwrap_or(0)
+                };
+                *word = if offset.x >= 0 {
+                    let low = source_word(i.checked_sub(word_shift + 1));
+                    let high = source_word(i.checked_sub(word_shift));
+                    if bit_shift == 0 {
+                        high
+                    } else {
+                        (high << bit_shift) | (low >> (WORD_BITS - bit_shift))
+                    }
+                } else {
+                    let low = source_word(Some(i + word_shift));
+                    let high = source_word(Some(i + word_shift + 1));
+                    if bit_shift == 0 {
+                        low
+                    } else {
+                        (low >> bit_shift) | (high << (WORD_BITS - bit_shift))
+                    }
+                };
+            }
+        }
+
+        shifted.mask_trailing_bits();
+        shifted
+    }
+
+    /// All positions that are orthogonally adjacent to a set bit (not including the set bits
+    /// themselves, unless they are adjacent to another set bit)
+    pub fn orthogonal_spread(&self) -> Self {
+        self.shifted((1, 0)) | self.shifted((-1, 0)) | self.shifted((0, 1)) | self.shifted((0, -1))
+    }
+
+    pub fn render(&self) -> Renderer<'_, bool> {
+        let bounds = (self.num_rows > 0 && self.num_cols > 0)
+            .then(|| ((0, 0).into(), (self.num_cols - 1, self.num_rows - 1).into()).into());
+        Renderer::new(
+            |pos| self.contains(pos).then_some(if self.get(*pos) { &true } else { &false }),
+            |&b| if b { '#' } else { '.' },
+            bounds,
+        )
+    }
+}
+
+impl std::fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.render().fmt(f)
+    }
+}
+
+macro_rules! impl_bit_op {
+    ($op:ident, $fn:ident, $op_assign:ident, $fn_assign:ident) => {
+        impl $op_assign<&BitGrid> for BitGrid {
+            fn $fn_assign(&mut self, rhs: &BitGrid) {
+                assert_eq!((self.num_rows, self.num_cols), (rhs.num_rows, rhs.num_cols));
+                for (lhs, rhs) in self.data.iter_mut().zip_eq(rhs.data.iter()) {
+                    lhs.$fn_assign(rhs);
+                }
+            }
+        }
+
+        impl $op_assign for BitGrid {
+            fn $fn_assign(&mut self, rhs: BitGrid) {
+                self.$fn_assign(&rhs);
+            }
+        }
+
+        impl $op<&BitGrid> for BitGrid {
+            type Output = BitGrid;
+
+            fn $fn(mut self, rhs: &BitGrid) -> Self::Output {
+                self.$fn_assign(rhs);
+                self
+            }
+        }
+
+        impl $op for BitGrid {
+            type Output = BitGrid;
+
+            fn $fn(mut self, rhs: BitGrid) -> Self::Output {
+                self.$fn_assign(&rhs);
+                self
+            }
+        }
+    };
+}
+impl_bit_op!(BitAnd, bitand, BitAndAssign, bitand_assign);
+impl_bit_op!(BitOr, bitor, BitOrAssign, bitor_assign);
+impl_bit_op!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+impl Not for BitGrid {
+    type Output = BitGrid;
+
+    fn not(mut self) -> Self::Output {
+        for word in self.data.iter_mut() {
+            *word = !*word;
+        }
+        self.mask_trailing_bits();
+        self
+    }
+}
+
+/// Set bits are walkable
+impl UnweightedGrid for BitGrid {
+    fn neighbors<'a, 'b: 'a>(&'a self, node: &'b Vec2D) -> impl Iterator<Item = Vec2D> + 'a {
+        node.orthogonal_neighbors().filter(move |neighbor| self.get(*neighbor))
+    }
+}
+
+impl graphs::UnweightedGraph for BitGrid {
+    type Node = Vec2D;
+
+    fn neighbors<'a, 'b: 'a>(
+        &'a self,
+        node: &'b Self::Node,
+    ) -> impl Iterator<Item = Self::Node> + 'a {
+        assert!(self.contains(node));
+        UnweightedGrid::neighbors(self, node)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{graphs::floodfill, Input};
+    use pretty_assertions::assert_eq;
+
+    fn from_str(s: &str) -> BitGrid {
+        BitGrid::from_grid(&Input::from(s).char_grid(), |&c| c == '#')
+    }
+
+    #[test]
+    fn get_and_set() {
+        let mut grid = BitGrid::new(3, 70);
+        assert!(grid.is_empty());
+
+        grid.set((0, 0), true);
+        grid.set((69, 2), true);
+        grid.set((64, 1), true);
+        assert!(grid.get((0, 0)));
+        assert!(grid.get((69, 2)));
+        assert!(grid.get((64, 1)));
+        assert!(!grid.get((1, 0)));
+        assert!(!grid.get((70, 2)));
+        assert!(!grid.get((-1, 0)));
+
+        assert_eq!(grid.count_ones(), 3);
+        assert_eq!(grid.row_count_ones(1), 1);
+        assert_eq!(
+            grid.ones().collect_vec(),
+            vec![Vec2D::new(0, 0), Vec2D::new(64, 1), Vec2D::new(69, 2)]
+        );
+
+        grid.set((0, 0), false);
+        assert!(!grid.get((0, 0)));
+        assert_eq!(grid.count_ones(), 2);
+    }
+
+    #[test]
+    fn conversion() {
+        let grid = Input::from("#..\n.#.").char_grid();
+        let bit_grid = BitGrid::from_grid(&grid, |&c| c == '#');
+        assert_eq!(bit_grid.to_grid(), Grid::from([[true, false, false], [false, true, false]]));
+        assert_eq!(bit_grid.to_string(), "#..\n.#.\n");
+    }
+
+    #[test]
+    fn bit_ops() {
+        let lhs = from_str("##..\n#.#.");
+        let rhs = from_str("#.#.\n#..#");
+        assert_eq!((lhs.clone() & &rhs).to_string(), "#...\n#...\n");
+        assert_eq!((lhs.clone() | &rhs).to_string(), "###.\n#.##\n");
+        assert_eq!((lhs.clone() ^ rhs).to_string(), ".##.\n..##\n");
+        assert_eq!((!lhs).to_string(), "..##\n.#.#\n");
+    }
+
+    #[test]
+    fn shifted() {
+        let grid = from_str("#..#\n.#..\n....");
+        assert_eq!(grid.shifted((1, 0)).to_string(), ".#..\n..#.\n....\n");
+        assert_eq!(grid.shifted((-1, 0)).to_string(), "..#.\n#...\n....\n");
+        assert_eq!(grid.shifted((0, 1)).to_string(), "....\n#..#\n.#..\n");
+        assert_eq!(grid.shifted((1, -1)).to_string(), "..#.\n....\n....\n");
+        assert_eq!(grid.shifted((4, 0)), BitGrid::new(3, 4));
+
+        // Shifts across word boundaries
+        let mut wide = BitGrid::new(1, 200);
+        wide.set((0, 0), true);
+        wide.set((63, 0), true);
+        wide.set((130, 0), true);
+        assert_eq!(wide.shifted((1, 0)).ones().map(|p| p.x).collect_vec(), vec![1, 64, 131]);
+        assert_eq!(wide.shifted((65, 0)).ones().map(|p| p.x).collect_vec(), vec![65, 128, 195]);
+        assert_eq!(wide.shifted((64, 0)).ones().map(|p| p.x).collect_vec(), vec![64, 127, 194]);
+        assert_eq!(wide.shifted((-1, 0)).ones().map(|p| p.x).collect_vec(), vec![62, 129]);
+        assert_eq!(wide.shifted((-64, 0)).ones().map(|p| p.x).collect_vec(), vec![66]);
+        assert_eq!(wide.shifted((-63, 0)).ones().map(|p| p.x).collect_vec(), vec![0, 67]);
+        assert_eq!(wide.shifted((70, 0)).ones().map(|p| p.x).collect_vec(), vec![70, 133]);
+    }
+
+    #[test]
+    fn step_frontier() {
+        let grid = Input::from(
+            r#"
+...........
+.....###.#.
+.###.##..#.
+..#.#...#..
+....#.#....
+.##..S####.
+.##..#...#.
+.......##..
+.##.#.####.
+.##..##.##.
+..........."#,
+        )
+        .char_grid();
+        let open = BitGrid::from_grid(&grid, |&c| c != '#');
+        let start = grid.iter().find_map(|(pos, &c)| (c == 'S').then_some(pos)).unwrap();
+
+        let mut frontier = BitGrid::from_positions(grid.num_rows(), grid.num_cols(), [start]);
+        for _ in 0..6 {
+            frontier = frontier.orthogonal_spread() & &open;
+        }
+        assert_eq!(frontier.count_ones(), 16);
+
+        assert_eq!(floodfill(&open, start).len(), open.count_ones());
+    }
+}
@@ -1,8 +1,15 @@
-use std::str::{Chars, FromStr};
+use std::{
+    collections::HashMap,
+    str::{Chars, FromStr},
+};
 
 use itertools::Itertools;
 
-use crate::{grid::Grid, math::Vec2D, sparse_grid::SparseGrid};
+use crate::{
+    grid::{Grid, GridCell},
+    math::Vec2D,
+    sparse_grid::SparseGrid,
+};
 
 pub struct Input {
     pub raw: String,
@@ -62,7 +69,6 @@ impl Input {
 
     pub fn parse_sparse_grid<T: FromStr>(&self, sep: &str) -> SparseGrid<T>
     where
-        T: std::hash::Hash,
         <T as std::str::FromStr>::Err: std::fmt::Debug,
     {
         self.lines()
@@ -87,6 +93,31 @@ impl Input {
         self.char_grid().iter().map(|(pos, c)| (pos, *c)).collect()
     }
 
+    /// Returns the grid together with the positions of T::MARKERS, which are replaced by the
+    /// default cell. Each marker must appear at most once.
+    pub fn grid_of<T: GridCell>(&self) -> (Grid<T>, HashMap<char, Vec2D>) {
+        let mut markers = HashMap::new();
+        let grid = self
+            .lines()
+            .enumerate()
+            .map(|(y, line)| {
+                line.chars()
+                    .enumerate()
+                    .map(|(x, c)| {
+                        if T::MARKERS.contains(&c) {
+                            let duplicate = markers.insert(c, Vec2D::from((x, y))).is_some();
+                            assert!(!duplicate, "Marker {c} appears more than once");
+                            T::default()
+                        } else {
+                            T::from_char(c).unwrap_or_else(|| panic!("Invalid cell {c} at ({x}, {y})"))
+                        }
+                    })
+                    .collect_vec()
+            })
+            .collect();
+        (grid, markers)
+    }
+
     pub fn parse_grid_from_characters<T: FromStr>(&self) -> Grid<T>
     where
         <T as std::str::FromStr>::Err: std::fmt::Debug,
@@ -211,6 +242,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn grid_of() {
+        #[derive(aoc_derive::GridCell, Default, Debug, PartialEq, Eq, Clone, Copy)]
+        #[markers = "SE"]
+        enum Tile {
+            #[default]
+            #[cell = '.']
+            Open,
+            #[cell = '#']
+            Wall,
+        }
+
+        let input = Input { raw: "S.#\n#.E\n".to_string() };
+        let (grid, markers) = input.grid_of::<Tile>();
+        assert_eq!(
+            grid,
+            Grid::from([[Tile::Open, Tile::Open, Tile::Wall], [Tile::Wall, Tile::Open, Tile::Open]])
+        );
+        assert_eq!(markers, HashMap::from([('S', Vec2D::new(0, 0)), ('E', Vec2D::new(2, 1))]));
+
+        assert_eq!(grid.render(Tile::to_char).to_string(), "..#\n#..\n");
+    }
+
     #[test]
     fn parse_grid_from_characters() {
         let input = Input { raw: "1234\n4567\n".to_string() };
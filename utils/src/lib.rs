@@ -1,5 +1,8 @@
 use std::{fmt::Display, str::FromStr};
 
+// Allows code generated by aoc_derive to refer to utils:: from within this crate
+extern crate self as utils;
+
 pub mod math;
 
 mod input;
@@ -10,6 +13,15 @@ pub mod graphs;
 pub mod grid;
 pub mod sparse_grid;
 
+pub mod render;
+
+pub mod automaton;
+
+pub mod cycle;
+
+mod memo;
+pub use memo::*;
+
 mod regex_helper;
 pub use regex_helper::*;
 
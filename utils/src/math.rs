@@ -2,5 +2,26 @@ mod vec2d;
 pub use vec2d::*;
 mod box2d;
 pub use box2d::*;
+mod box3d;
+pub use box3d::*;
+mod direction;
+pub use direction::*;
+mod interval_set;
+pub use interval_set::*;
+mod line;
+pub use line::*;
 mod line1d;
 pub use line1d::*;
+mod linear_system;
+pub use linear_system::*;
+mod neighborhood;
+pub use neighborhood::*;
+pub mod number_theory;
+mod polygon;
+pub use polygon::*;
+mod polynomial;
+pub use polynomial::*;
+mod vec3d;
+pub use vec3d::*;
+mod vecnd;
+pub use vecnd::*;
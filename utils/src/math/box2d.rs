@@ -53,10 +53,62 @@ impl Box2D {
         self.upper = Vec2D::new(self.upper.x.max(point.x), self.upper.y.max(point.y));
     }
 
+    /// True if lower > upper in any coordinate, e.g. for a box collected from no points
+    pub fn is_empty(&self) -> bool {
+        self.lower.x > self.upper.x || self.lower.y > self.upper.y
+    }
+
     pub fn num_points(&self) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
         ((self.upper.x - self.lower.x + 1) * (self.upper.y - self.lower.y + 1)) as usize
     }
 
+    /// Number of points inside, since both bounds are inclusive
+    pub fn area(&self) -> usize {
+        self.num_points()
+    }
+
+    /// Including the case where the boxes only share an edge or corner
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(
+            (self.lower.x.max(other.lower.x), self.lower.y.max(other.lower.y)),
+            (self.upper.x.min(other.upper.x), self.upper.y.min(other.upper.y)),
+        );
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// Smallest box containing both boxes
+    pub fn union_bounds(&self, other: &Self) -> Self {
+        Self::new(
+            (self.lower.x.min(other.lower.x), self.lower.y.min(other.lower.y)),
+            (self.upper.x.max(other.upper.x), self.upper.y.max(other.upper.y)),
+        )
+    }
+
+    /// Splits the points of self that are not inside other into (up to 4) disjoint boxes
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![self.clone()];
+        };
+
+        // Full-width slabs above and below the cut, and the parts left and right of it
+        [
+            Self::new(self.lower, (self.upper.x, cut.lower.y - 1)),
+            Self::new((self.lower.x, cut.upper.y + 1), self.upper),
+            Self::new((self.lower.x, cut.lower.y), (cut.lower.x - 1, cut.upper.y)),
+            Self::new((cut.upper.x + 1, cut.lower.y), (self.upper.x, cut.upper.y)),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect()
+    }
+
     pub fn points_inside(&self) -> impl Iterator<Item = Vec2D> + '_ {
         (self.lower.x..=self.upper.x)
             .cartesian_product(self.lower.y..=self.upper.y)
@@ -117,6 +169,62 @@ mod tests {
         assert_eq!(box2d, Box2D::new(Vec2D::new(-1, -1), Vec2D::new(3, 3)));
     }
 
+    #[test]
+    fn test_intersection() {
+        let box2d = Box2D::new((0, 0), (4, 4));
+        assert_eq!(
+            box2d.intersection(&Box2D::new((2, 3), (6, 8))),
+            Some(Box2D::new((2, 3), (4, 4)))
+        );
+        assert_eq!(
+            box2d.intersection(&Box2D::new((1, 1), (2, 2))),
+            Some(Box2D::new((1, 1), (2, 2)))
+        );
+        assert_eq!(
+            box2d.intersection(&Box2D::new((4, 4), (5, 5))),
+            Some(Box2D::new((4, 4), (4, 4)))
+        );
+        assert_eq!(box2d.intersection(&Box2D::new((5, 0), (6, 4))), None);
+        assert_eq!(box2d.intersection(&Box2D::new((0, -2), (4, -1))), None);
+
+        assert!(box2d.overlaps(&Box2D::new((-1, -1), (5, 5))));
+        assert!(box2d.overlaps(&Box2D::new((-1, -1), (0, 0))));
+        assert!(!box2d.overlaps(&Box2D::new((-1, -1), (-1, 5))));
+    }
+
+    #[test]
+    fn test_union_bounds_and_area() {
+        let box2d = Box2D::new((0, 0), (1, 1));
+        assert_eq!(box2d.union_bounds(&Box2D::new((3, -1), (4, 0))), Box2D::new((0, -1), (4, 1)));
+        assert_eq!(box2d.area(), 4);
+        assert_eq!(Box2D::new((0, 0), (2, 1)).area(), 6);
+
+        let empty: Box2D = std::iter::empty().collect();
+        assert!(empty.is_empty());
+        assert_eq!(empty.area(), 0);
+    }
+
+    #[test]
+    fn test_subtract() {
+        let box2d = Box2D::new((0, 0), (4, 4));
+        let check = |other: Box2D, expected_area: usize| {
+            let parts = box2d.subtract(&other);
+            assert_eq!(parts.iter().map(Box2D::area).sum::<usize>(), expected_area);
+
+            let points = parts.iter().flat_map(|part| part.points_inside()).collect::<Vec<_>>();
+            assert_eq!(points.len(), points.iter().collect::<HashSet<_>>().len(), "{parts:?}");
+            assert!(points.iter().all(|p| box2d.contains(p) && !other.contains(p)));
+        };
+
+        check(Box2D::new((1, 1), (3, 3)), 16);
+        check(Box2D::new((-1, -1), (5, 5)), 0);
+        check(Box2D::new((2, -1), (5, 5)), 10);
+        check(Box2D::new((4, 4), (5, 5)), 24);
+        check(Box2D::new((10, 10), (11, 11)), 25);
+
+        assert_eq!(box2d.subtract(&Box2D::new((0, 0), (4, 1))), vec![Box2D::new((0, 2), (4, 4))]);
+    }
+
     #[test]
     fn test_from_points() {
         let box2d: Box2D = [Vec2D::new(0, 0), Vec2D::new(2, 2), Vec2D::new(1, 1), Vec2D::new(1, 0)]
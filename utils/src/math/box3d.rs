@@ -0,0 +1,228 @@
+use crate::math::{Box2D, Vec3D};
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct Box3D {
+    pub lower: Vec3D,
+    pub upper: Vec3D,
+}
+
+impl From<(Vec3D, Vec3D)> for Box3D {
+    fn from((lower, upper): (Vec3D, Vec3D)) -> Self {
+        Self { lower, upper }
+    }
+}
+
+impl FromIterator<Vec3D> for Box3D {
+    fn from_iter<I: IntoIterator<Item = Vec3D>>(iter: I) -> Self {
+        let mut box3d = Self::new((i64::MAX, i64::MAX, i64::MAX), (i64::MIN, i64::MIN, i64::MIN));
+        for point in iter {
+            box3d.extend(point);
+        }
+        box3d
+    }
+}
+
+impl Box3D {
+    pub fn new(lower: impl Into<Vec3D>, upper: impl Into<Vec3D>) -> Self {
+        Self { lower: lower.into(), upper: upper.into() }
+    }
+
+    pub fn lower(&self) -> Vec3D {
+        self.lower
+    }
+
+    pub fn upper(&self) -> Vec3D {
+        self.upper
+    }
+
+    pub fn contains(&self, point: &Vec3D) -> bool {
+        point.x >= self.lower.x
+            && point.y >= self.lower.y
+            && point.z >= self.lower.z
+            && point.x <= self.upper.x
+            && point.y <= self.upper.y
+            && point.z <= self.upper.z
+    }
+
+    pub fn extend(&mut self, point: impl Into<Vec3D>) {
+        let point = point.into();
+        self.lower = Vec3D::new(
+            self.lower.x.min(point.x),
+            self.lower.y.min(point.y),
+            self.lower.z.min(point.z),
+        );
+        self.upper = Vec3D::new(
+            self.upper.x.max(point.x),
+            self.upper.y.max(point.y),
+            self.upper.z.max(point.z),
+        );
+    }
+
+    /// Moves the whole box by `offset`
+    pub fn translated(&self, offset: impl Into<Vec3D>) -> Self {
+        let offset = offset.into();
+        Self::new(self.lower + offset, self.upper + offset)
+    }
+
+    /// True if lower > upper in any coordinate, e.g. for a box collected from no points
+    pub fn is_empty(&self) -> bool {
+        self.lower.x > self.upper.x || self.lower.y > self.upper.y || self.lower.z > self.upper.z
+    }
+
+    /// Number of points inside, since both bounds are inclusive
+    pub fn volume(&self) -> usize {
+        if self.is_empty() {
+            return 0;
+        }
+        let size = self.upper - self.lower + (1, 1, 1);
+        (size.x * size.y * size.z) as usize
+    }
+
+    /// Drops the z coordinate
+    pub fn project_xy(&self) -> Box2D {
+        Box2D::new(self.lower.xy(), self.upper.xy())
+    }
+
+    /// Including the case where the boxes only share a face, edge or corner
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        let intersection = Self::new(
+            (
+                self.lower.x.max(other.lower.x),
+                self.lower.y.max(other.lower.y),
+                self.lower.z.max(other.lower.z),
+            ),
+            (
+                self.upper.x.min(other.upper.x),
+                self.upper.y.min(other.upper.y),
+                self.upper.z.min(other.upper.z),
+            ),
+        );
+        (!intersection.is_empty()).then_some(intersection)
+    }
+
+    /// Smallest box containing both boxes
+    pub fn union_bounds(&self, other: &Self) -> Self {
+        Self::new(
+            (
+                self.lower.x.min(other.lower.x),
+                self.lower.y.min(other.lower.y),
+                self.lower.z.min(other.lower.z),
+            ),
+            (
+                self.upper.x.max(other.upper.x),
+                self.upper.y.max(other.upper.y),
+                self.upper.z.max(other.upper.z),
+            ),
+        )
+    }
+
+    /// Splits the points of self that are not inside other into (up to 6) disjoint boxes
+    pub fn subtract(&self, other: &Self) -> Vec<Self> {
+        let Some(cut) = self.intersection(other) else {
+            return vec![self.clone()];
+        };
+
+        let (lower, upper) = (self.lower, self.upper);
+        [
+            // Full slabs below and above the cut in z
+            Self::new(lower, (upper.x, upper.y, cut.lower.z - 1)),
+            Self::new((lower.x, lower.y, cut.upper.z + 1), upper),
+            // Within the z range of the cut: full-width parts in front of and behind it in y
+            Self::new((lower.x, lower.y, cut.lower.z), (upper.x, cut.lower.y - 1, cut.upper.z)),
+            Self::new((lower.x, cut.upper.y + 1, cut.lower.z), (upper.x, upper.y, cut.upper.z)),
+            // Within the y and z range of the cut: the parts left and right of it
+            Self::new(
+                (lower.x, cut.lower.y, cut.lower.z),
+                (cut.lower.x - 1, cut.upper.y, cut.upper.z),
+            ),
+            Self::new(
+                (cut.upper.x + 1, cut.lower.y, cut.lower.z),
+                (upper.x, cut.upper.y, cut.upper.z),
+            ),
+        ]
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .collect()
+    }
+
+    pub fn points_inside(&self) -> impl Iterator<Item = Vec3D> + '_ {
+        (self.lower.x..=self.upper.x).flat_map(move |x| {
+            (self.lower.y..=self.upper.y)
+                .flat_map(move |y| (self.lower.z..=self.upper.z).map(move |z| Vec3D::new(x, y, z)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn test_contains_and_extend() {
+        let mut box3d = Box3D::new((0, 0, 0), (2, 2, 2));
+        assert!(box3d.contains(&Vec3D::new(0, 1, 2)));
+        assert!(!box3d.contains(&Vec3D::new(0, 1, 3)));
+
+        box3d.extend((-1, 3, 1));
+        assert_eq!(box3d, Box3D::new((-1, 0, 0), (2, 3, 2)));
+
+        let from_points: Box3D = [Vec3D::new(1, 2, 3), Vec3D::new(-1, 5, 0)].into_iter().collect();
+        assert_eq!(from_points, Box3D::new((-1, 2, 0), (1, 5, 3)));
+    }
+
+    #[test]
+    fn test_volume_and_projection() {
+        let box3d = Box3D::new((0, 0, 1), (1, 2, 3));
+        assert_eq!(box3d.volume(), 18);
+        assert_eq!(box3d.volume(), box3d.points_inside().count());
+        assert_eq!(box3d.project_xy(), Box2D::new((0, 0), (1, 2)));
+        assert_eq!(box3d.translated((0, 0, -1)), Box3D::new((0, 0, 0), (1, 2, 2)));
+
+        let empty: Box3D = std::iter::empty().collect();
+        assert!(empty.is_empty());
+        assert_eq!(empty.volume(), 0);
+    }
+
+    #[test]
+    fn test_intersection_and_union() {
+        let box3d = Box3D::new((0, 0, 0), (4, 4, 4));
+        assert_eq!(
+            box3d.intersection(&Box3D::new((2, 3, -1), (6, 8, 1))),
+            Some(Box3D::new((2, 3, 0), (4, 4, 1)))
+        );
+        assert_eq!(box3d.intersection(&Box3D::new((0, 0, 5), (4, 4, 6))), None);
+        assert!(box3d.overlaps(&Box3D::new((4, 4, 4), (5, 5, 5))));
+        assert!(!box3d.overlaps(&Box3D::new((5, 4, 4), (5, 5, 5))));
+
+        assert_eq!(
+            box3d.union_bounds(&Box3D::new((5, -1, 2), (6, 0, 3))),
+            Box3D::new((0, -1, 0), (6, 4, 4))
+        );
+    }
+
+    #[test]
+    fn test_subtract() {
+        let box3d = Box3D::new((0, 0, 0), (3, 3, 3));
+        let check = |other: Box3D, expected_volume: usize| {
+            let parts = box3d.subtract(&other);
+            assert_eq!(parts.iter().map(Box3D::volume).sum::<usize>(), expected_volume);
+
+            let points = parts.iter().flat_map(|part| part.points_inside()).collect::<Vec<_>>();
+            assert_eq!(points.len(), points.iter().collect::<HashSet<_>>().len(), "{parts:?}");
+            assert!(points.iter().all(|p| box3d.contains(p) && !other.contains(p)));
+        };
+
+        check(Box3D::new((1, 1, 1), (2, 2, 2)), 64 - 8);
+        check(Box3D::new((-1, -1, -1), (5, 5, 5)), 0);
+        check(Box3D::new((2, -1, 1), (5, 5, 1)), 64 - 8);
+        check(Box3D::new((3, 3, 3), (5, 5, 5)), 63);
+        check(Box3D::new((10, 10, 10), (11, 11, 11)), 64);
+        assert_eq!(box3d.subtract(&Box3D::new((1, 1, 1), (2, 2, 2))).len(), 6);
+    }
+}
@@ -0,0 +1,168 @@
+use std::str::FromStr;
+
+use crate::math::Vec2D;
+
+/// A compass direction in our y-down coordinates, i.e. North is (0, -1) and East is (1, 0).
+/// The variants are in clockwise order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Direction {
+    North,
+    NorthEast,
+    East,
+    SouthEast,
+    South,
+    SouthWest,
+    West,
+    NorthWest,
+}
+
+use Direction::*;
+
+impl Direction {
+    /// The 4 orthogonal directions, clockwise starting at North
+    pub const ORTHOGONAL: [Direction; 4] = [North, East, South, West];
+
+    /// All 8 directions, clockwise starting at North
+    pub const ALL: [Direction; 8] =
+        [North, NorthEast, East, SouthEast, South, SouthWest, West, NorthWest];
+
+    /// Rotates clockwise by `eighths` * 45 degrees
+    fn rotated(self, eighths: usize) -> Self {
+        Self::ALL[(self as usize + eighths) % 8]
+    }
+
+    /// Rotates by 90 degrees clockwise
+    pub fn turn_right(self) -> Self {
+        self.rotated(2)
+    }
+
+    /// Rotates by 90 degrees counterclockwise
+    pub fn turn_left(self) -> Self {
+        self.rotated(6)
+    }
+
+    pub fn opposite(self) -> Self {
+        self.rotated(4)
+    }
+
+    pub fn is_diagonal(self) -> bool {
+        matches!(self, NorthEast | SouthEast | SouthWest | NorthWest)
+    }
+
+    pub fn heading(self) -> Vec2D {
+        match self {
+            North => Vec2D::new(0, -1),
+            NorthEast => Vec2D::new(1, -1),
+            East => Vec2D::new(1, 0),
+            SouthEast => Vec2D::new(1, 1),
+            South => Vec2D::new(0, 1),
+            SouthWest => Vec2D::new(-1, 1),
+            West => Vec2D::new(-1, 0),
+            NorthWest => Vec2D::new(-1, -1),
+        }
+    }
+}
+
+impl From<Direction> for Vec2D {
+    fn from(direction: Direction) -> Self {
+        direction.heading()
+    }
+}
+
+/// Fails if the vector is not one of the 8 unit headings
+impl TryFrom<Vec2D> for Direction {
+    type Error = String;
+
+    fn try_from(heading: Vec2D) -> Result<Self, Self::Error> {
+        Self::ALL
+            .into_iter()
+            .find(|direction| direction.heading() == heading)
+            .ok_or_else(|| format!("{heading:?} is not a unit heading"))
+    }
+}
+
+/// Accepts U/D/L/R, N/E/S/W, ^/v/</> and arrows
+impl TryFrom<char> for Direction {
+    type Error = String;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        Ok(match c {
+            'U' | 'N' | '^' | '↑' => North,
+            'R' | 'E' | '>' | '→' => East,
+            'D' | 'S' | 'v' | '↓' => South,
+            'L' | 'W' | '<' | '←' => West,
+            '↗' => NorthEast,
+            '↘' => SouthEast,
+            '↙' => SouthWest,
+            '↖' => NorthWest,
+            _ => return Err(format!("Invalid direction: {c}")),
+        })
+    }
+}
+
+/// Same as `TryFrom<char>`, plus NE/SE/SW/NW for the diagonals
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => Self::try_from(c),
+            _ => match s {
+                "NE" => Ok(NorthEast),
+                "SE" => Ok(SouthEast),
+                "SW" => Ok(SouthWest),
+                "NW" => Ok(NorthWest),
+                _ => Err(format!("Invalid direction: {s}")),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        assert_eq!(North.turn_right(), East);
+        assert_eq!(North.turn_left(), West);
+        assert_eq!(West.turn_right(), North);
+        assert_eq!(NorthEast.turn_right(), SouthEast);
+        assert_eq!(South.opposite(), North);
+        assert_eq!(SouthWest.opposite(), NorthEast);
+
+        for direction in Direction::ALL {
+            assert_eq!(direction.turn_left().turn_right(), direction);
+            assert_eq!(direction.turn_right().turn_right(), direction.opposite());
+            assert_eq!(direction.opposite().heading(), -direction.heading());
+            // Consistent with Vec2D's rotations
+            assert_eq!(direction.turn_right().heading(), direction.heading().rotated_right());
+            assert_eq!(direction.turn_left().heading(), direction.heading().rotated_left());
+        }
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Vec2D::from(East), (1, 0));
+        assert_eq!(Vec2D::new(3, 4) + North, (3, 3));
+        assert_eq!(Direction::try_from(Vec2D::new(-1, 1)), Ok(SouthWest));
+        assert!(Direction::try_from(Vec2D::new(2, 0)).is_err());
+        assert!(Direction::ORTHOGONAL.iter().all(|d| !d.is_diagonal()));
+        assert_eq!(Direction::ALL.iter().filter(|d| d.is_diagonal()).count(), 4);
+    }
+
+    #[test]
+    fn parse() {
+        for (s, direction) in
+            [("U", North), ("E", East), ("v", South), ("←", West), ("NW", NorthWest)]
+        {
+            assert_eq!(s.parse(), Ok(direction));
+        }
+        assert_eq!(Direction::try_from('>'), Ok(East));
+        assert_eq!(Direction::try_from('↘'), Ok(SouthEast));
+        assert!("X".parse::<Direction>().is_err());
+        assert!("".parse::<Direction>().is_err());
+        assert!("UU".parse::<Direction>().is_err());
+    }
+}
@@ -0,0 +1,305 @@
+use crate::math::Line1D;
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent inclusive lines
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalSet {
+    lines: Vec<Line1D>,
+}
+
+impl FromIterator<Line1D> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Line1D>>(iter: I) -> Self {
+        let mut lines = iter.into_iter().filter(|line| !line.is_empty()).collect::<Vec<_>>();
+        lines.sort_by_key(Line1D::start);
+
+        let mut merged: Vec<Line1D> = Vec::with_capacity(lines.len());
+        for line in lines {
+            match merged.last_mut() {
+                Some(last) if line.start() <= last.end() + 1 => {
+                    *last = Line1D::new((last.start(), last.end().max(line.end())));
+                }
+                _ => merged.push(line),
+            }
+        }
+        Self { lines: merged }
+    }
+}
+
+impl Extend<Line1D> for IntervalSet {
+    fn extend<I: IntoIterator<Item = Line1D>>(&mut self, iter: I) {
+        *self = self.lines.iter().copied().chain(iter).collect();
+    }
+}
+
+impl From<Line1D> for IntervalSet {
+    fn from(line: Line1D) -> Self {
+        std::iter::once(line).collect()
+    }
+}
+
+impl IntervalSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert(&mut self, line: Line1D) {
+        self.extend(std::iter::once(line));
+    }
+
+    /// The disjoint lines in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = Line1D> + '_ {
+        self.lines.iter().copied()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    pub fn num_intervals(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Number of points in the set
+    pub fn total_len(&self) -> i64 {
+        self.lines.iter().map(Line1D::len).sum()
+    }
+
+    /// Smallest line containing the whole set
+    pub fn bounds(&self) -> Option<Line1D> {
+        Some(Line1D::new((self.lines.first()?.start(), self.lines.last()?.end())))
+    }
+
+    pub fn contains(&self, point: impl Into<i64>) -> bool {
+        let point = point.into();
+        let index = self.lines.partition_point(|line| line.end() < point);
+        self.lines.get(index).is_some_and(|line| line.contains(point))
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        self.iter().chain(other.iter()).collect()
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let (mut i, mut j) = (0, 0);
+        let mut lines = Vec::new();
+        while i < self.lines.len() && j < other.lines.len() {
+            let (a, b) = (self.lines[i], other.lines[j]);
+            lines.extend(a.line_from_overlap(&b));
+            // Whichever line ends first can't overlap anything else from the other set
+            if a.end() < b.end() {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { lines }
+    }
+
+    /// All points of `bounds` that are not in the set
+    pub fn complement_within(&self, bounds: Line1D) -> Self {
+        let mut lines = Vec::new();
+        let mut next_start = bounds.start();
+        for line in self.iter().filter(|line| line.overlaps(&bounds)) {
+            if line.start() > next_start {
+                lines.push(Line1D::new((next_start, line.start() - 1)));
+            }
+            next_start = line.end() + 1;
+        }
+        if next_start <= bounds.end() {
+            lines.push(Line1D::new((next_start, bounds.end())));
+        }
+        Self { lines }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        match self.bounds() {
+            Some(bounds) => self.intersection(&other.complement_within(bounds)),
+            None => Self::new(),
+        }
+    }
+}
+
+/// Maps each point of a source line to point + offset, and every point outside of all source
+/// lines to itself. Useful for mapping whole ranges at once, e.g. 2023 day 5.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct IntervalMap {
+    /// Sorted by start, sources don't overlap
+    pieces: Vec<(Line1D, i64)>,
+}
+
+impl FromIterator<(Line1D, i64)> for IntervalMap {
+    fn from_iter<I: IntoIterator<Item = (Line1D, i64)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (source, offset) in iter {
+            map.insert(source, offset);
+        }
+        map
+    }
+}
+
+impl IntervalMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Panics if the source overlaps with an already inserted one
+    pub fn insert(&mut self, source: Line1D, offset: i64) {
+        let index = self.pieces.partition_point(|(line, _)| line.end() < source.start());
+        if let Some((existing, _)) = self.pieces.get(index) {
+            assert!(!existing.overlaps(&source), "{source:?} overlaps with {existing:?}");
+        }
+        self.pieces.insert(index, (source, offset));
+    }
+
+    /// The source lines with their offsets in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = (Line1D, i64)> + '_ {
+        self.pieces.iter().copied()
+    }
+
+    pub fn get(&self, point: i64) -> i64 {
+        let index = self.pieces.partition_point(|(line, _)| line.end() < point);
+        match self.pieces.get(index) {
+            Some((line, offset)) if line.contains(point) => point + offset,
+            _ => point,
+        }
+    }
+
+    /// Maps every point of the set
+    pub fn apply(&self, set: &IntervalSet) -> IntervalSet {
+        let mut mapped = Vec::new();
+        for line in set.iter() {
+            let first = self.pieces.partition_point(|(source, _)| source.end() < line.start());
+            let mut next_start = line.start();
+            for (source, offset) in &self.pieces[first..] {
+                let Some(cut) = line.line_from_overlap(source) else {
+                    break;
+                };
+                if cut.start() > next_start {
+                    mapped.push(Line1D::new((next_start, cut.start() - 1)));
+                }
+                mapped.push(Line1D::new((cut.start() + offset, cut.end() + offset)));
+                next_start = cut.end() + 1;
+            }
+            if next_start <= line.end() {
+                mapped.push(Line1D::new((next_start, line.end())));
+            }
+        }
+        mapped.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn set(lines: &[(i64, i64)]) -> IntervalSet {
+        lines.iter().map(|&line| Line1D::new(line)).collect()
+    }
+
+    fn points(set: &IntervalSet) -> BTreeSet<i64> {
+        set.iter().flat_map(|line| line.start()..=line.end()).collect()
+    }
+
+    #[test]
+    fn test_from_iter_merges() {
+        let merged = set(&[(5, 7), (0, 2), (3, 3), (6, 10), (20, 19)]);
+        assert_eq!(
+            merged.iter().collect::<Vec<_>>(),
+            vec![Line1D::new((0, 3)), Line1D::new((5, 10))]
+        );
+        assert_eq!(merged.total_len(), 10);
+        assert_eq!(merged.bounds(), Some(Line1D::new((0, 10))));
+        assert!(merged.contains(3) && !merged.contains(4) && merged.contains(10));
+    }
+
+    #[test]
+    fn test_operations() {
+        let a = set(&[(0, 10), (20, 30)]);
+        let b = set(&[(5, 25)]);
+        assert_eq!(a.union(&b), set(&[(0, 30)]));
+        assert_eq!(a.intersection(&b), set(&[(5, 10), (20, 25)]));
+        assert_eq!(a.difference(&b), set(&[(0, 4), (26, 30)]));
+        assert_eq!(b.difference(&a), set(&[(11, 19)]));
+        assert_eq!(
+            a.complement_within(Line1D::new((-5, 35))),
+            set(&[(-5, -1), (11, 19), (31, 35)])
+        );
+        assert_eq!(IntervalSet::new().complement_within(Line1D::new((1, 2))), set(&[(1, 2)]));
+    }
+
+    #[test]
+    fn test_map() {
+        // seed-to-soil map from the day 5 example
+        let map: IntervalMap = [(Line1D::new((98, 99)), 50 - 98), (Line1D::new((50, 97)), 52 - 50)]
+            .into_iter()
+            .collect();
+        assert_eq!(map.get(79), 81);
+        assert_eq!(map.get(14), 14);
+        assert_eq!(map.get(99), 51);
+        assert_eq!(map.apply(&set(&[(79, 92), (55, 67)])), set(&[(81, 94), (57, 69)]));
+        assert_eq!(map.apply(&set(&[(45, 100)])), set(&[(45, 49), (50, 51), (52, 99), (100, 100)]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_map_overlapping_sources() {
+        let _: IntervalMap =
+            [(Line1D::new((0, 5)), 1), (Line1D::new((5, 6)), 2)].into_iter().collect();
+    }
+
+    fn arb_set() -> impl Strategy<Value = IntervalSet> {
+        prop::collection::vec((-30..30_i64, 0..10_i64), 0..6).prop_map(|lines| {
+            lines.into_iter().map(|(start, len)| Line1D::new((start, start + len))).collect()
+        })
+    }
+
+    fn arb_map() -> impl Strategy<Value = IntervalMap> {
+        prop::collection::vec((-30..30_i64, 0..10_i64, -40..40_i64), 0..6).prop_map(|pieces| {
+            let mut map = IntervalMap::new();
+            for (start, len, offset) in pieces {
+                let source = Line1D::new((start, start + len));
+                if map.iter().all(|(existing, _)| !existing.overlaps(&source)) {
+                    map.insert(source, offset);
+                }
+            }
+            map
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_set_operations(a in arb_set(), b in arb_set(), start in -40..40_i64, len in 0..50_i64) {
+            let (pa, pb) = (points(&a), points(&b));
+            prop_assert_eq!(points(&a.union(&b)), &pa | &pb);
+            prop_assert_eq!(points(&a.intersection(&b)), &pa & &pb);
+            prop_assert_eq!(points(&a.difference(&b)), &pa - &pb);
+            prop_assert_eq!(a.total_len(), pa.len() as i64);
+
+            let bounds = Line1D::new((start, start + len));
+            let all = (start..=start + len).collect::<BTreeSet<_>>();
+            prop_assert_eq!(points(&a.complement_within(bounds)), &all - &pa);
+            for point in -45..45 {
+                prop_assert_eq!(a.contains(point), pa.contains(&point));
+            }
+        }
+
+        #[test]
+        fn prop_set_is_normalized(a in arb_set()) {
+            let lines = a.iter().collect::<Vec<_>>();
+            prop_assert!(lines.windows(2).all(|w| w[0].end() + 1 < w[1].start()));
+        }
+
+        #[test]
+        fn prop_map_apply(map in arb_map(), set in arb_set()) {
+            let expected = points(&set).into_iter().map(|point| {
+                map.iter()
+                    .find(|(source, _)| source.contains(point))
+                    .map_or(point, |(_, offset)| point + offset)
+            });
+            prop_assert_eq!(points(&map.apply(&set)), expected.collect::<BTreeSet<_>>());
+        }
+    }
+}
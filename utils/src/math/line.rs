@@ -0,0 +1,337 @@
+use num::{rational::Ratio, Zero};
+
+use crate::math::{Box2D, Vec2D, Vec3D};
+
+/// Exact fractions. i128 leaves enough room for the products of coordinates around 1e14 with
+/// velocities around 1e3, as in 2023 day 24.
+pub type Rational = Ratio<i128>;
+
+/// How two lines (or segments) meet. For a Point, `t` and `u` are the parameters of the
+/// intersection on the first and second line, i.e. `first.at(t) == second.at(u)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LineIntersection {
+    Point {
+        t: Rational,
+        u: Rational,
+    },
+    /// Parallel, but not on the same line
+    Parallel,
+    /// On the same line
+    Coincident,
+    /// Only in 3D: neither parallel nor intersecting
+    Skew,
+}
+
+fn wide(v: Vec2D) -> Vec2D<i128> {
+    Vec2D { x: v.x as i128, y: v.y as i128 }
+}
+
+fn wide3(v: Vec3D) -> Vec3D<i128> {
+    Vec3D { x: v.x as i128, y: v.y as i128, z: v.z as i128 }
+}
+
+fn in_unit_interval(t: &Rational) -> bool {
+    *t >= Rational::zero() && *t <= Rational::from_integer(1)
+}
+
+/// True if the parameter ranges [0, 1] and [min(a, b), max(a, b)] overlap
+fn ranges_overlap(a: Rational, b: Rational) -> bool {
+    a.min(b) <= Rational::from_integer(1) && a.max(b) >= Rational::zero()
+}
+
+/// All points `origin + t * direction` for any t. The direction must not be zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line2D {
+    pub origin: Vec2D,
+    pub direction: Vec2D,
+}
+
+impl Line2D {
+    pub fn new(origin: impl Into<Vec2D>, direction: impl Into<Vec2D>) -> Self {
+        Self { origin: origin.into(), direction: direction.into() }
+    }
+
+    /// The line through both points, with t = 0 at `a` and t = 1 at `b`
+    pub fn through(a: impl Into<Vec2D>, b: impl Into<Vec2D>) -> Self {
+        let a = a.into();
+        Self::new(a, b.into() - a)
+    }
+
+    pub fn at(&self, t: Rational) -> Vec2D<Rational> {
+        let (origin, direction) = (wide(self.origin), wide(self.direction));
+        Vec2D {
+            x: Rational::from_integer(origin.x) + t * direction.x,
+            y: Rational::from_integer(origin.y) + t * direction.y,
+        }
+    }
+
+    pub fn contains(&self, point: impl Into<Vec2D>) -> bool {
+        wide(self.direction).cross(wide(point.into() - self.origin)) == 0
+    }
+
+    pub fn intersect(&self, other: &Self) -> LineIntersection {
+        let (d1, d2) = (wide(self.direction), wide(other.direction));
+        let diff = wide(other.origin - self.origin);
+        let det = d1.cross(d2);
+        if det == 0 {
+            return if diff.cross(d1) == 0 {
+                LineIntersection::Coincident
+            } else {
+                LineIntersection::Parallel
+            };
+        }
+        LineIntersection::Point {
+            t: Rational::new(diff.cross(d2), det),
+            u: Rational::new(diff.cross(d1), det),
+        }
+    }
+
+    /// True if the lines cross at t >= 0 and u >= 0, at a point inside of `bounds`. Coincident
+    /// lines don't count, since they don't have a single intersection.
+    pub fn future_intersection_inside(&self, other: &Self, bounds: &Box2D) -> bool {
+        let LineIntersection::Point { t, u } = self.intersect(other) else {
+            return false;
+        };
+        if t < Rational::zero() || u < Rational::zero() {
+            return false;
+        }
+        let (lower, upper) = (wide(bounds.lower()), wide(bounds.upper()));
+        self.at(t).inside_box(
+            Vec2D { x: Rational::from_integer(lower.x), y: Rational::from_integer(lower.y) },
+            Vec2D { x: Rational::from_integer(upper.x), y: Rational::from_integer(upper.y) },
+        )
+    }
+}
+
+/// The points `start + t * (end - start)` for t in [0, 1]. Start and end must differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment2D {
+    pub start: Vec2D,
+    pub end: Vec2D,
+}
+
+impl Segment2D {
+    pub fn new(start: impl Into<Vec2D>, end: impl Into<Vec2D>) -> Self {
+        Self { start: start.into(), end: end.into() }
+    }
+
+    pub fn line(&self) -> Line2D {
+        Line2D::through(self.start, self.end)
+    }
+
+    /// Parameter of a point on the line through this segment
+    fn param_of(&self, point: Vec2D) -> Rational {
+        let direction = wide(self.end - self.start);
+        Rational::new(wide(point - self.start).dot(direction), direction.dot(direction))
+    }
+
+    /// None if the segments don't touch, Coincident if they overlap on the same line
+    pub fn intersect(&self, other: &Self) -> Option<LineIntersection> {
+        match self.line().intersect(&other.line()) {
+            point @ LineIntersection::Point { t, u } => {
+                (in_unit_interval(&t) && in_unit_interval(&u)).then_some(point)
+            }
+            LineIntersection::Coincident => {
+                ranges_overlap(self.param_of(other.start), self.param_of(other.end))
+                    .then_some(LineIntersection::Coincident)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// All points `origin + t * direction` for any t. The direction must not be zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Line3D {
+    pub origin: Vec3D,
+    pub direction: Vec3D,
+}
+
+impl Line3D {
+    pub fn new(origin: impl Into<Vec3D>, direction: impl Into<Vec3D>) -> Self {
+        Self { origin: origin.into(), direction: direction.into() }
+    }
+
+    /// The line through both points, with t = 0 at `a` and t = 1 at `b`
+    pub fn through(a: impl Into<Vec3D>, b: impl Into<Vec3D>) -> Self {
+        let a = a.into();
+        Self::new(a, b.into() - a)
+    }
+
+    /// Projection onto the xy plane. Note that the direction might become zero.
+    pub fn xy(&self) -> Line2D {
+        Line2D::new(self.origin.xy(), self.direction.xy())
+    }
+
+    pub fn at(&self, t: Rational) -> [Rational; 3] {
+        let (origin, direction) = (wide3(self.origin), wide3(self.direction));
+        [(origin.x, direction.x), (origin.y, direction.y), (origin.z, direction.z)]
+            .map(|(origin, direction)| Rational::from_integer(origin) + t * direction)
+    }
+
+    pub fn contains(&self, point: impl Into<Vec3D>) -> bool {
+        wide3(self.direction).cross(wide3(point.into() - self.origin)).is_zero()
+    }
+
+    pub fn intersect(&self, other: &Self) -> LineIntersection {
+        let (d1, d2) = (wide3(self.direction), wide3(other.direction));
+        let diff = wide3(other.origin) - wide3(self.origin);
+        let normal = d1.cross(d2);
+        if normal.is_zero() {
+            return if diff.cross(d1).is_zero() {
+                LineIntersection::Coincident
+            } else {
+                LineIntersection::Parallel
+            };
+        }
+        if diff.dot(normal) != 0 {
+            return LineIntersection::Skew;
+        }
+        let normal_sq = normal.dot(normal);
+        LineIntersection::Point {
+            t: Rational::new(diff.cross(d2).dot(normal), normal_sq),
+            u: Rational::new(diff.cross(d1).dot(normal), normal_sq),
+        }
+    }
+}
+
+/// The points `start + t * (end - start)` for t in [0, 1]. Start and end must differ.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment3D {
+    pub start: Vec3D,
+    pub end: Vec3D,
+}
+
+impl Segment3D {
+    pub fn new(start: impl Into<Vec3D>, end: impl Into<Vec3D>) -> Self {
+        Self { start: start.into(), end: end.into() }
+    }
+
+    pub fn line(&self) -> Line3D {
+        Line3D::through(self.start, self.end)
+    }
+
+    /// Parameter of a point on the line through this segment
+    fn param_of(&self, point: Vec3D) -> Rational {
+        let direction = wide3(self.end - self.start);
+        Rational::new(wide3(point - self.start).dot(direction), direction.dot(direction))
+    }
+
+    /// None if the segments don't touch, Coincident if they overlap on the same line
+    pub fn intersect(&self, other: &Self) -> Option<LineIntersection> {
+        match self.line().intersect(&other.line()) {
+            point @ LineIntersection::Point { t, u } => {
+                (in_unit_interval(&t) && in_unit_interval(&u)).then_some(point)
+            }
+            LineIntersection::Coincident => {
+                ranges_overlap(self.param_of(other.start), self.param_of(other.end))
+                    .then_some(LineIntersection::Coincident)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r(numer: i128, denom: i128) -> Rational {
+        Rational::new(numer, denom)
+    }
+
+    #[test]
+    fn test_line2d() {
+        let line = Line2D::new((0, 0), (2, 1));
+        assert_eq!(
+            line.intersect(&Line2D::new((0, 3), (1, -1))),
+            LineIntersection::Point { t: r(1, 1), u: r(2, 1) }
+        );
+        assert_eq!(line.at(r(1, 2)), Vec2D { x: r(1, 1), y: r(1, 2) });
+
+        let crossing = Line2D::through((1, 0), (0, 1));
+        let LineIntersection::Point { t, u } = line.intersect(&crossing) else {
+            panic!("expected a point");
+        };
+        assert_eq!(line.at(t), crossing.at(u));
+        assert_eq!(line.at(t), Vec2D { x: r(2, 3), y: r(1, 3) });
+
+        assert_eq!(line.intersect(&Line2D::new((0, 1), (-4, -2))), LineIntersection::Parallel);
+        assert_eq!(line.intersect(&Line2D::new((4, 2), (-4, -2))), LineIntersection::Coincident);
+        assert!(line.contains((-6, -3)));
+        assert!(!line.contains((1, 1)));
+    }
+
+    #[test]
+    fn test_hailstones() {
+        // 2023 day 24 example, ignoring the z axis
+        let hailstones = [
+            ((19, 13), (-2, 1)),
+            ((18, 19), (-1, -1)),
+            ((20, 25), (-2, -2)),
+            ((12, 31), (-1, -2)),
+            ((20, 19), (1, -5)),
+        ]
+        .map(|(origin, direction)| Line2D::new(origin, direction));
+        let bounds = Box2D::new((7, 7), (27, 27));
+
+        let mut count = 0;
+        for (i, a) in hailstones.iter().enumerate() {
+            for b in &hailstones[i + 1..] {
+                count += a.future_intersection_inside(b, &bounds) as usize;
+            }
+        }
+        assert_eq!(count, 2);
+
+        // Large coordinates must not lose precision
+        let far = Line2D::new((200_000_000_000_000_i64, 300_000_000_000_001_i64), (-3, 7));
+        let other = Line2D::new((200_000_000_000_000_i64, 300_000_000_000_000_i64), (-3, 7));
+        assert_eq!(far.intersect(&other), LineIntersection::Parallel);
+    }
+
+    #[test]
+    fn test_segment2d() {
+        let segment = Segment2D::new((0, 0), (4, 4));
+        assert!(segment.intersect(&Segment2D::new((0, 4), (4, 0))).is_some());
+        assert!(segment.intersect(&Segment2D::new((0, 4), (1, 3))).is_none());
+        assert!(segment.intersect(&Segment2D::new((4, 4), (5, 0))).is_some());
+        assert_eq!(
+            segment.intersect(&Segment2D::new((3, 3), (6, 6))),
+            Some(LineIntersection::Coincident)
+        );
+        assert_eq!(segment.intersect(&Segment2D::new((5, 5), (6, 6))), None);
+        assert_eq!(segment.intersect(&Segment2D::new((1, 0), (5, 4))), None);
+    }
+
+    #[test]
+    fn test_line3d() {
+        let line = Line3D::new((0, 0, 0), (1, 1, 1));
+        let LineIntersection::Point { t, u } = line.intersect(&Line3D::new((2, 2, 0), (0, 0, 1)))
+        else {
+            panic!("expected a point");
+        };
+        assert_eq!((t, u), (r(2, 1), r(2, 1)));
+        assert_eq!(line.at(t), [r(2, 1); 3]);
+
+        assert_eq!(line.intersect(&Line3D::new((1, 0, 0), (0, 1, 0))), LineIntersection::Skew);
+        assert_eq!(line.intersect(&Line3D::new((1, 0, 0), (2, 2, 2))), LineIntersection::Parallel);
+        assert_eq!(
+            line.intersect(&Line3D::new((3, 3, 3), (-1, -1, -1))),
+            LineIntersection::Coincident
+        );
+        assert_eq!(line.xy(), Line2D::new((0, 0), (1, 1)));
+        assert!(line.contains((-2, -2, -2)));
+    }
+
+    #[test]
+    fn test_segment3d() {
+        let segment = Segment3D::new((0, 0, 0), (2, 2, 2));
+        assert!(segment.intersect(&Segment3D::new((1, 1, 0), (1, 1, 5))).is_some());
+        assert!(segment.intersect(&Segment3D::new((3, 3, 0), (3, 3, 5))).is_none());
+        assert!(segment.intersect(&Segment3D::new((0, 1, 0), (1, 0, 5))).is_none());
+        assert_eq!(
+            segment.intersect(&Segment3D::new((2, 2, 2), (4, 4, 4))),
+            Some(LineIntersection::Coincident)
+        );
+    }
+}
@@ -16,6 +16,24 @@ impl Line1D {
         }
     }
 
+    pub fn start(&self) -> i64 {
+        self.start
+    }
+
+    pub fn end(&self) -> i64 {
+        self.end
+    }
+
+    /// Number of points on the line, since both ends are inclusive
+    pub fn len(&self) -> i64 {
+        (self.end - self.start + 1).max(0)
+    }
+
+    /// True if start > end
+    pub fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
+
     pub fn contains(&self, point: impl Into<i64>) -> bool {
         let point = point.into();
         self.start <= point && point <= self.end
@@ -27,7 +45,7 @@ impl Line1D {
 
     /// Including the case where the lines share a point
     pub fn overlaps(&self, line: &Self) -> bool {
-        self.contains(line.start) || self.contains(line.end)
+        self.start <= line.end && line.start <= self.end
     }
 
     pub fn line_from_overlap(&self, line: &Self) -> Option<Self> {
@@ -73,6 +91,8 @@ mod tests {
         assert!(line.overlaps(&Line1D::new((5, 10))));
         assert!(line.overlaps(&Line1D::new((-6, 10))));
         assert!(line.overlaps(&Line1D::new((-5, 11))));
+        assert!(line.overlaps(&Line1D::new((-6, 11))));
+        assert!(Line1D::new((0, 0)).overlaps(&line));
         assert!(!line.overlaps(&Line1D::new((-7, -6))));
         assert!(!line.overlaps(&Line1D::new((11, 14))));
     }
@@ -104,6 +124,10 @@ mod tests {
             line.line_from_overlap(&Line1D::new((-3, 11))),
             Some(Line1D::new((-3, 10)))
         );
+        assert_eq!(
+            line.line_from_overlap(&Line1D::new((-6, 11))),
+            Some(Line1D::new((-5, 10)))
+        );
         assert_eq!(line.line_from_overlap(&Line1D::new((-7, -6))), None);
         assert_eq!(line.line_from_overlap(&Line1D::new((11, 14))), None);
     }
@@ -0,0 +1,201 @@
+use num::Num;
+
+/// Result of solving a LinearSystem
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinearSolution<T> {
+    Unique(Vec<T>),
+    /// Infinitely many solutions. `particular` is the one where all free unknowns are zero.
+    Underdetermined {
+        particular: Vec<T>,
+        rank: usize,
+    },
+    /// The equations contradict each other
+    Inconsistent,
+}
+
+impl<T> LinearSolution<T> {
+    pub fn unique(self) -> Option<Vec<T>> {
+        match self {
+            Self::Unique(solution) => Some(solution),
+            _ => None,
+        }
+    }
+}
+
+/// Equations `a_0 * x_0 + ... + a_(n-1) * x_(n-1) = b` solved exactly with Gaussian elimination.
+/// T should be a field without rounding errors, like `Ratio<i128>` or `BigRational` if the
+/// intermediate values can get large. There can be more equations than unknowns, in which case
+/// they either have to be consistent, or `least_squares` can be used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LinearSystem<T> {
+    num_unknowns: usize,
+    /// Augmented rows, i.e. the coefficients followed by the right hand side
+    rows: Vec<Vec<T>>,
+}
+
+impl<T: Num + Clone> LinearSystem<T> {
+    pub fn new(num_unknowns: usize) -> Self {
+        Self { num_unknowns, rows: Vec::new() }
+    }
+
+    pub fn num_unknowns(&self) -> usize {
+        self.num_unknowns
+    }
+
+    pub fn num_equations(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Panics if the number of coefficients doesn't match the number of unknowns
+    pub fn add_equation(&mut self, coefficients: impl IntoIterator<Item = T>, rhs: T) {
+        let mut row = coefficients.into_iter().collect::<Vec<_>>();
+        assert_eq!(row.len(), self.num_unknowns, "wrong number of coefficients");
+        row.push(rhs);
+        self.rows.push(row);
+    }
+
+    /// Brings the augmented rows into reduced row echelon form, returns the pivot column of each
+    /// of the first `rank` rows.
+    fn reduce(rows: &mut [Vec<T>], num_unknowns: usize) -> Vec<usize> {
+        let mut pivots = Vec::new();
+        for col in 0..num_unknowns {
+            let rank = pivots.len();
+            let Some(pivot_row) = (rank..rows.len()).find(|&row| !rows[row][col].is_zero()) else {
+                continue;
+            };
+            rows.swap(rank, pivot_row);
+
+            let pivot = rows[rank][col].clone();
+            for value in &mut rows[rank][col..] {
+                *value = value.clone() / pivot.clone();
+            }
+
+            let pivot_row = rows[rank].clone();
+            for (i, row) in rows.iter_mut().enumerate() {
+                let factor = row[col].clone();
+                if i == rank || factor.is_zero() {
+                    continue;
+                }
+                for (value, pivot_value) in row[col..].iter_mut().zip(&pivot_row[col..]) {
+                    *value = value.clone() - factor.clone() * pivot_value.clone();
+                }
+            }
+            pivots.push(col);
+        }
+        pivots
+    }
+
+    /// Number of linearly independent equations, ignoring the right hand sides
+    pub fn rank(&self) -> usize {
+        let mut rows = self.rows.clone();
+        Self::reduce(&mut rows, self.num_unknowns).len()
+    }
+
+    pub fn solve(&self) -> LinearSolution<T> {
+        let mut rows = self.rows.clone();
+        let pivots = Self::reduce(&mut rows, self.num_unknowns);
+        let rank = pivots.len();
+
+        // All coefficients of the remaining rows are zero now, so their right hand side has to be
+        if rows[rank..].iter().any(|row| !row[self.num_unknowns].is_zero()) {
+            return LinearSolution::Inconsistent;
+        }
+
+        let mut solution = vec![T::zero(); self.num_unknowns];
+        for (row, &col) in pivots.iter().enumerate() {
+            solution[col] = rows[row][self.num_unknowns].clone();
+        }
+
+        if rank == self.num_unknowns {
+            LinearSolution::Unique(solution)
+        } else {
+            LinearSolution::Underdetermined { particular: solution, rank }
+        }
+    }
+
+    /// Minimizes the squared error over all equations by solving the normal equations
+    /// A^T A x = A^T b. Never inconsistent, but can still be underdetermined.
+    pub fn least_squares(&self) -> LinearSolution<T> {
+        let n = self.num_unknowns;
+        let mut normal = Self::new(n);
+        for i in 0..n {
+            let sum_products = |j: usize| {
+                self.rows.iter().fold(T::zero(), |sum, row| sum + row[i].clone() * row[j].clone())
+            };
+            normal.add_equation((0..n).map(sum_products), sum_products(n));
+        }
+        normal.solve()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num::{rational::Ratio, BigRational};
+
+    use super::*;
+
+    fn system(equations: &[(&[i128], i128)]) -> LinearSystem<Ratio<i128>> {
+        let mut system = LinearSystem::new(equations[0].0.len());
+        for (coefficients, rhs) in equations {
+            system.add_equation(coefficients.iter().map(|&c| c.into()), (*rhs).into());
+        }
+        system
+    }
+
+    fn ratios(values: &[(i128, i128)]) -> Vec<Ratio<i128>> {
+        values.iter().map(|&(numer, denom)| Ratio::new(numer, denom)).collect()
+    }
+
+    #[test]
+    fn test_unique() {
+        // x + y = 3, 2x - 2y = 1
+        let simple = system(&[(&[1, 1], 3), (&[2, -2], 1)]);
+        assert_eq!(simple.rank(), 2);
+        assert_eq!(simple.solve(), LinearSolution::Unique(ratios(&[(7, 4), (5, 4)])));
+
+        // Needs a row swap since the first pivot candidate is zero
+        let swapped = system(&[(&[0, 1, 1], 5), (&[1, 0, 1], 4), (&[1, 1, 0], 3)]);
+        assert_eq!(swapped.solve().unique(), Some(ratios(&[(1, 1), (2, 1), (3, 1)])));
+    }
+
+    #[test]
+    fn test_overdetermined() {
+        let consistent = system(&[(&[1, 0], 1), (&[0, 1], 2), (&[1, 1], 3), (&[2, 1], 4)]);
+        assert_eq!(consistent.num_equations(), 4);
+        assert_eq!(consistent.rank(), 2);
+        assert_eq!(consistent.solve(), LinearSolution::Unique(ratios(&[(1, 1), (2, 1)])));
+
+        let inconsistent = system(&[(&[1], 1), (&[1], 2), (&[1], 6)]);
+        assert_eq!(inconsistent.solve(), LinearSolution::Inconsistent);
+        assert_eq!(inconsistent.least_squares(), LinearSolution::Unique(ratios(&[(3, 1)])));
+
+        // Fitting y = a + b * x through (0, 1), (1, 2), (2, 4)
+        let line = system(&[(&[1, 0], 1), (&[1, 1], 2), (&[1, 2], 4)]);
+        assert_eq!(line.least_squares(), LinearSolution::Unique(ratios(&[(5, 6), (3, 2)])));
+    }
+
+    #[test]
+    fn test_underdetermined() {
+        let system = system(&[(&[1, 1, 1], 6), (&[2, 2, 2], 12), (&[0, 1, 2], 8)]);
+        assert_eq!(system.rank(), 2);
+        assert_eq!(
+            system.solve(),
+            LinearSolution::Underdetermined {
+                particular: ratios(&[(-2, 1), (8, 1), (0, 1)]),
+                rank: 2
+            }
+        );
+        assert_eq!(system.solve().unique(), None);
+    }
+
+    #[test]
+    fn test_big_rational() {
+        let big = |n: i64| BigRational::from_integer(n.into());
+        let mut system = LinearSystem::new(2);
+        let huge = 400_000_000_000_000;
+        system.add_equation([big(huge), big(huge - 1)], big(1));
+        system.add_equation([big(huge + 1), big(huge)], big(2));
+        // The determinant is 1, so Cramer's rule gives integers
+        assert_eq!(system.solve().unique(), Some(vec![big(2 - huge), big(huge - 1)]));
+    }
+}
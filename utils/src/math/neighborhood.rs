@@ -0,0 +1,167 @@
+use std::borrow::Cow;
+
+use itertools::Either;
+
+use crate::math::Vec2D;
+
+const VON_NEUMANN: [Vec2D; 4] =
+    [Vec2D { x: 1, y: 0 }, Vec2D { x: -1, y: 0 }, Vec2D { x: 0, y: 1 }, Vec2D { x: 0, y: -1 }];
+
+const DIAGONAL: [Vec2D; 4] =
+    [Vec2D { x: 1, y: 1 }, Vec2D { x: -1, y: 1 }, Vec2D { x: 1, y: -1 }, Vec2D { x: -1, y: -1 }];
+
+const MOORE: [Vec2D; 8] = [
+    VON_NEUMANN[0],
+    VON_NEUMANN[1],
+    VON_NEUMANN[2],
+    VON_NEUMANN[3],
+    DIAGONAL[0],
+    DIAGONAL[1],
+    DIAGONAL[2],
+    DIAGONAL[3],
+];
+
+/// Axial coordinates (q, r) = (x, y), see https://www.redblobgames.com/grids/hexagons/
+const HEX_AXIAL: [Vec2D; 6] = [
+    Vec2D { x: 1, y: 0 },
+    Vec2D { x: 1, y: -1 },
+    Vec2D { x: 0, y: -1 },
+    Vec2D { x: -1, y: 0 },
+    Vec2D { x: -1, y: 1 },
+    Vec2D { x: 0, y: 1 },
+];
+
+/// Which positions count as neighbors of a position, accepted by Vec2D, Grid and SparseGrid
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Neighborhood {
+    /// The 4 orthogonal neighbors
+    VonNeumann,
+    /// The 4 diagonal neighbors
+    Diagonal,
+    /// The 4 orthogonal neighbors, followed by the 4 diagonal ones
+    Moore,
+    /// The 6 neighbors of a hexagon in axial coordinates
+    HexAxial,
+    /// All positions with a manhattan distance of 1..=radius, sorted by distance
+    Manhattan(u32),
+    Custom(Vec<Vec2D>),
+}
+
+impl Neighborhood {
+    pub fn custom<V: Into<Vec2D>>(offsets: impl IntoIterator<Item = V>) -> Self {
+        Self::Custom(offsets.into_iter().map(Into::into).collect())
+    }
+
+    pub fn knight_moves() -> Self {
+        Self::custom([(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)])
+    }
+
+    pub fn offsets(&self) -> Cow<'_, [Vec2D]> {
+        match self {
+            Self::Manhattan(radius) => Cow::Owned(manhattan_offsets(*radius).collect()),
+            _ => Cow::Borrowed(self.fixed_offsets().unwrap()),
+        }
+    }
+
+    /// The offsets of all neighborhoods except Manhattan, which are computed on the fly
+    fn fixed_offsets(&self) -> Option<&[Vec2D]> {
+        match self {
+            Self::VonNeumann => Some(&VON_NEUMANN),
+            Self::Diagonal => Some(&DIAGONAL),
+            Self::Moore => Some(&MOORE),
+            Self::HexAxial => Some(&HEX_AXIAL),
+            Self::Manhattan(_) => None,
+            Self::Custom(offsets) => Some(offsets),
+        }
+    }
+
+    /// Doesn't allocate, not even for Manhattan
+    pub fn neighbors_of(&self, pos: Vec2D) -> impl Iterator<Item = Vec2D> + '_ {
+        let offsets = match self {
+            Self::Manhattan(radius) => Either::Right(manhattan_offsets(*radius)),
+            _ => Either::Left(self.fixed_offsets().unwrap().iter().copied()),
+        };
+        offsets.map(move |offset| pos + offset)
+    }
+}
+
+/// Sorted by distance, and by x within the same distance
+fn manhattan_offsets(radius: u32) -> impl Iterator<Item = Vec2D> {
+    let radius = radius as i64;
+    (1..=radius).flat_map(|dist| {
+        (-dist..=dist).flat_map(move |x| {
+            let y = dist - x.abs();
+            // For y = 0, both would be the same offset
+            [Vec2D::new(x, -y), Vec2D::new(x, y)].into_iter().take(if y == 0 { 1 } else { 2 })
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use itertools::Itertools;
+
+    use super::*;
+
+    #[test]
+    fn moore() {
+        assert_eq!(
+            Neighborhood::Moore.offsets().iter().collect::<HashSet<_>>(),
+            Neighborhood::VonNeumann
+                .offsets()
+                .iter()
+                .chain(Neighborhood::Diagonal.offsets().iter())
+                .collect()
+        );
+        assert_eq!(&Neighborhood::Moore.offsets()[..4], &*Neighborhood::VonNeumann.offsets());
+    }
+
+    #[test]
+    fn manhattan() {
+        assert_eq!(
+            Neighborhood::Manhattan(1).offsets().iter().collect::<HashSet<_>>(),
+            Neighborhood::VonNeumann.offsets().iter().collect()
+        );
+
+        let offsets = Neighborhood::Manhattan(2).offsets();
+        assert_eq!(offsets.len(), 12);
+        assert!(offsets.iter().all(|offset| (1..=2).contains(&offset.manhattan_dist())));
+        assert!(offsets.iter().map(Vec2D::manhattan_dist).tuple_windows().all(|(a, b)| a <= b));
+        assert_eq!(offsets.iter().unique().count(), 12);
+
+        assert_eq!(Neighborhood::Manhattan(0).offsets().len(), 0);
+        assert_eq!(
+            Neighborhood::Manhattan(2).neighbors_of(Vec2D::new(1, 1)).collect_vec(),
+            offsets.iter().map(|&offset| Vec2D::new(1, 1) + offset).collect_vec()
+        );
+    }
+
+    #[test]
+    fn custom() {
+        let knight = Neighborhood::knight_moves();
+        assert_eq!(knight.offsets().len(), 8);
+        assert!(knight
+            .offsets()
+            .iter()
+            .all(|offset| offset.abs() == (1, 2) || offset.abs() == (2, 1)));
+
+        assert_eq!(
+            Neighborhood::custom([(0, 2)]).neighbors_of(Vec2D::new(1, 1)).collect_vec(),
+            vec![Vec2D::new(1, 3)]
+        );
+    }
+
+    #[test]
+    fn hex() {
+        // Every hex neighbor is also a neighbor of two other hex neighbors
+        let neighbors = Neighborhood::HexAxial.neighbors_of(Vec2D::zero()).collect::<HashSet<_>>();
+        for n in &neighbors {
+            assert_eq!(
+                Neighborhood::HexAxial.neighbors_of(*n).filter(|m| neighbors.contains(m)).count(),
+                2
+            );
+        }
+    }
+}
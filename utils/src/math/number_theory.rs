@@ -0,0 +1,159 @@
+//! gcd/lcm, modular arithmetic and the Chinese remainder theorem. Intermediate products are
+//! computed in i128, so they can't overflow, but crt returns None if its result doesn't fit
+//! into an i64.
+
+use num::Integer;
+
+/// gcd of all values, 0 for no values
+pub fn gcd<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::zero(), |acc, value| acc.gcd(&value))
+}
+
+/// lcm of all values, 1 for no values
+pub fn lcm<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::one(), |acc, value| acc.lcm(&value))
+}
+
+/// Returns (g, x, y) with g = gcd(a, b) = a * x + b * y
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let num::integer::ExtendedGcd { gcd, x, y, .. } = a.extended_gcd(&b);
+    (gcd, x, y)
+}
+
+/// x in 0..modulus with a * x = 1 (mod modulus), None if a and modulus are not coprime
+pub fn mod_inverse(a: i64, modulus: i64) -> Option<i64> {
+    let (gcd, x, _) = extended_gcd(a.rem_euclid(modulus), modulus);
+    (gcd == 1).then(|| x.rem_euclid(modulus))
+}
+
+/// base^exp mod modulus by repeated squaring, in 0..modulus
+pub fn mod_pow(base: i64, mut exp: u64, modulus: i64) -> i64 {
+    let modulus = modulus as i128;
+    let mut base = (base as i128).rem_euclid(modulus);
+    let mut result = 1 % modulus;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result as i64
+}
+
+/// Solves x = residue (mod modulus) for all (residue, modulus) pairs. The moduli don't need to
+/// be coprime, but must be positive. Returns (x, lcm of all moduli) with x in 0..lcm, or None
+/// if the congruences contradict each other, a modulus is not positive or the lcm doesn't fit
+/// into an i64.
+pub fn crt(congruences: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    congruences.into_iter().try_fold((0, 1), |(x, m1), (residue, m2)| {
+        if m2 <= 0 {
+            return None;
+        }
+        let (x, m1, residue, m2) = (x as i128, m1 as i128, residue as i128, m2 as i128);
+        let g = m1.gcd(&m2);
+        let diff = residue - x;
+        if diff % g != 0 {
+            return None;
+        }
+
+        // x + m1 * k = residue (mod m2)  <=>  (m1 / g) * k = diff / g (mod m2 / g)
+        let reduced_modulus = m2 / g;
+        let inverse = mod_inverse(((m1 / g) % reduced_modulus) as i64, reduced_modulus as i64)?;
+        let k = (diff / g).rem_euclid(reduced_modulus) * inverse as i128 % reduced_modulus;
+
+        let lcm = m1 * reduced_modulus;
+        Some((i64::try_from((x + m1 * k).rem_euclid(lcm)).ok()?, i64::try_from(lcm).ok()?))
+    })
+}
+
+/// Combines events that happen at `start, start + period, start + 2 * period, ...` into the
+/// first time at which all of them happen together, and the period after which that repeats.
+/// This is what cycle detection usually gives us, e.g. for 2023 day 8 and day 20. None if the
+/// events never happen together, or not within i64.
+pub fn combine_cycles(cycles: impl IntoIterator<Item = (i64, i64)>) -> Option<(i64, i64)> {
+    let cycles = cycles.into_iter().collect::<Vec<_>>();
+    let (x, period) = crt(cycles.iter().copied())?;
+
+    // x is the smallest solution >= 0, but we also can't be earlier than any of the starts
+    let latest_start = cycles.iter().map(|&(start, _)| start).max().unwrap_or(0);
+    let first = if x >= latest_start {
+        x
+    } else {
+        Integer::div_ceil(&(latest_start - x), &period).checked_mul(period)?.checked_add(x)?
+    };
+    Some((first, period))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd([12, 18, 30]), 6);
+        assert_eq!(gcd::<i64>([]), 0);
+        assert_eq!(lcm([4_u64, 6, 10]), 60);
+        assert_eq!(lcm(Vec::<usize>::new()), 1);
+    }
+
+    #[test]
+    fn test_extended_gcd_and_inverse() {
+        let (g, x, y) = extended_gcd(240, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(-2, 3, 5), 2);
+        assert_eq!(mod_pow(7, 0, 1), 0);
+        // Would overflow without i128 intermediates
+        let p = 1_000_000_007;
+        assert_eq!(mod_pow(123_456_789, p as u64 - 1, p), 1);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Non-coprime moduli
+        assert_eq!(crt([(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        assert_eq!(crt([(-1, 5)]), Some((4, 5)));
+        assert_eq!(crt([]), Some((0, 1)));
+
+        // The product of the moduli is close to the i64 limit
+        let big = [(1, 999_999_937), (2, 999_999_929)];
+        let (x, m) = crt(big).unwrap();
+        assert!(big.iter().all(|&(r, n)| x % n == r));
+        assert_eq!(m, 999_999_937 * 999_999_929);
+
+        // The lcm doesn't fit into an i64
+        assert_eq!(crt([(1, (1 << 62) - 1), (2, (1 << 62) - 3)]), None);
+        assert_eq!(crt([(1, 0)]), None);
+        assert_eq!(crt([(1, 3), (1, -3)]), None);
+    }
+
+    #[test]
+    fn test_combine_cycles() {
+        assert_eq!(combine_cycles([(2, 2), (3, 3)]), Some((6, 6)));
+        // First hits must be after all starts, not just congruent
+        assert_eq!(combine_cycles([(10, 4), (2, 2)]), Some((10, 4)));
+        assert_eq!(combine_cycles([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(combine_cycles([(1, 2), (2, 4)]), None);
+
+        for cycles in [vec![(3, 4), (5, 6)], vec![(7, 3), (1, 5), (2, 2)]] {
+            let (first, period) = combine_cycles(cycles.clone()).unwrap();
+            let brute_force = (0..)
+                .find(|t| cycles.iter().all(|&(start, p)| *t >= start && (t - start) % p == 0))
+                .unwrap();
+            assert_eq!(first, brute_force);
+            assert_eq!(period, lcm(cycles.iter().map(|&(_, p)| p)));
+        }
+    }
+}
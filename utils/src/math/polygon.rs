@@ -0,0 +1,189 @@
+use num::Integer;
+
+use crate::math::Vec2D;
+
+/// Where a point lies relative to a polygon
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Location {
+    Inside,
+    OnBoundary,
+    Outside,
+}
+
+/// A simple polygon on the integer lattice, given by its vertices in order. The last vertex is
+/// implicitly connected to the first one.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polygon {
+    vertices: Vec<Vec2D>,
+}
+
+impl<V: Into<Vec2D>> FromIterator<V> for Polygon {
+    fn from_iter<I: IntoIterator<Item = V>>(iter: I) -> Self {
+        Self { vertices: iter.into_iter().map(Into::into).collect() }
+    }
+}
+
+impl Polygon {
+    pub fn new<V: Into<Vec2D>>(vertices: impl IntoIterator<Item = V>) -> Self {
+        vertices.into_iter().collect()
+    }
+
+    /// Walks from `start`, moving `distance` times into `direction` for each step, and uses the
+    /// position after each step as a vertex. The walk is expected to end at `start` again,
+    /// e.g. for the dig plan of 2023 day 18.
+    pub fn from_walk<D: Into<Vec2D>>(
+        start: impl Into<Vec2D>,
+        steps: impl IntoIterator<Item = (D, i64)>,
+    ) -> Self {
+        let vertices = steps
+            .into_iter()
+            .scan(start.into(), |pos, (direction, distance)| {
+                *pos += direction.into() * distance;
+                Some(*pos)
+            })
+            .collect();
+        Self { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Vec2D] {
+        &self.vertices
+    }
+
+    /// All edges as (from, to), including the one from the last to the first vertex
+    pub fn edges(&self) -> impl Iterator<Item = (Vec2D, Vec2D)> + '_ {
+        self.vertices.iter().zip(self.vertices.iter().cycle().skip(1)).map(|(&a, &b)| (a, b))
+    }
+
+    /// Twice the signed area, which is always an integer (shoelace formula). Positive if the
+    /// vertices are in clockwise order in our y-down coordinates.
+    pub fn doubled_signed_area(&self) -> i64 {
+        self.edges().map(|(a, b)| a.cross(b)).sum()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.doubled_signed_area().abs() as f64 / 2.0
+    }
+
+    /// Euclidean length of the boundary
+    pub fn perimeter(&self) -> f64 {
+        self.edges().map(|(a, b)| (b - a).length()).sum()
+    }
+
+    /// Number of lattice points on the boundary. Same as the perimeter if all edges are
+    /// horizontal or vertical.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| {
+                let edge = b - a;
+                edge.x.abs().gcd(&edge.y.abs())
+            })
+            .sum()
+    }
+
+    /// Number of lattice points strictly inside, via Pick's theorem: A = I + B/2 - 1
+    pub fn interior_points(&self) -> i64 {
+        (self.doubled_signed_area().abs() - self.boundary_points() + 2) / 2
+    }
+
+    /// Number of lattice points inside or on the boundary
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn locate(&self, point: impl Into<Vec2D>) -> Location {
+        let p = point.into();
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            if (b - a).cross(p - a) == 0
+                && p.inside_box((a.x.min(b.x), a.y.min(b.y)), (a.x.max(b.x), a.y.max(b.y)))
+            {
+                return Location::OnBoundary;
+            }
+
+            // Cast a ray to the right, counting the edges that cross its y half-open so that
+            // rays through a vertex are counted once.
+            if (a.y > p.y) != (b.y > p.y) {
+                let lhs = (p.x - a.x) * (b.y - a.y);
+                let rhs = (p.y - a.y) * (b.x - a.x);
+                if (b.y > a.y && lhs < rhs) || (b.y < a.y && lhs > rhs) {
+                    inside = !inside;
+                }
+            }
+        }
+
+        if inside {
+            Location::Inside
+        } else {
+            Location::Outside
+        }
+    }
+
+    /// Including points on the boundary
+    pub fn contains(&self, point: impl Into<Vec2D>) -> bool {
+        self.locate(point) != Location::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::math::Box2D;
+
+    use super::*;
+
+    #[test]
+    fn test_rectangle() {
+        let rectangle = Polygon::new([(0, 0), (4, 0), (4, 3), (0, 3)]);
+        assert_eq!(rectangle.doubled_signed_area(), 24);
+        assert_eq!(rectangle.area(), 12.0);
+        assert_eq!(rectangle.perimeter(), 14.0);
+        assert_eq!(rectangle.boundary_points(), 14);
+        assert_eq!(rectangle.interior_points(), 3 * 2);
+        assert_eq!(rectangle.lattice_points(), 5 * 4);
+
+        let reversed = Polygon::new(rectangle.vertices().iter().rev().copied());
+        assert_eq!(reversed.doubled_signed_area(), -24);
+        assert_eq!(reversed.interior_points(), 6);
+    }
+
+    #[test]
+    fn test_triangle() {
+        let triangle = Polygon::new([(0, 0), (4, 0), (0, 4)]);
+        assert_eq!(triangle.area(), 8.0);
+        assert_eq!(triangle.boundary_points(), 12);
+        assert_eq!(triangle.interior_points(), 3);
+        assert!((triangle.perimeter() - (8.0 + 32_f64.sqrt())).abs() < 1e-9);
+
+        assert_eq!(triangle.locate((1, 1)), Location::Inside);
+        assert_eq!(triangle.locate((2, 2)), Location::OnBoundary);
+        assert_eq!(triangle.locate((3, 3)), Location::Outside);
+        assert_eq!(triangle.locate((0, 0)), Location::OnBoundary);
+        assert_eq!(triangle.locate((5, 0)), Location::Outside);
+        assert_eq!(triangle.locate((-1, 0)), Location::Outside);
+    }
+
+    #[test]
+    fn test_locate_matches_pick() {
+        // U-shape with a notch, so that rays pass through vertices and along horizontal edges
+        let polygon =
+            Polygon::new([(0, 0), (2, 0), (2, 3), (4, 3), (4, 0), (6, 0), (6, 5), (0, 5)]);
+        let points = Box2D::new((-1, -1), (7, 6)).points_inside().collect::<Vec<_>>();
+        let count = |location| points.iter().filter(|&&p| polygon.locate(p) == location).count();
+
+        assert_eq!(count(Location::Inside) as i64, polygon.interior_points());
+        assert_eq!(count(Location::OnBoundary) as i64, polygon.boundary_points());
+        assert_eq!(polygon.locate((3, 2)), Location::Outside);
+        assert_eq!(polygon.locate((3, 4)), Location::Inside);
+        assert!(polygon.contains((3, 3)));
+    }
+
+    #[test]
+    fn test_from_walk() {
+        let polygon =
+            Polygon::from_walk((0, 0), [((1, 0), 6), ((0, 1), 5), ((-1, 0), 6), ((0, -1), 5)]);
+        assert_eq!(
+            polygon.vertices(),
+            &[Vec2D::new(6, 0), Vec2D::new(6, 5), Vec2D::new(0, 5), Vec2D::new(0, 0)]
+        );
+        assert_eq!(polygon.lattice_points(), 7 * 6);
+    }
+}
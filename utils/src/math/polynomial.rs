@@ -0,0 +1,225 @@
+use itertools::Itertools;
+use num::{One, Zero};
+
+use crate::math::Rational;
+
+/// A polynomial with exact rational coefficients, lowest degree first. Trailing zero
+/// coefficients are removed, so the zero polynomial has no coefficients at all.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Polynomial {
+    coefficients: Vec<Rational>,
+}
+
+impl Polynomial {
+    pub fn new<C: Into<Rational>>(coefficients: impl IntoIterator<Item = C>) -> Self {
+        let mut coefficients = coefficients.into_iter().map(Into::into).collect_vec();
+        while coefficients.last().is_some_and(Zero::is_zero) {
+            coefficients.pop();
+        }
+        Self { coefficients }
+    }
+
+    /// The unique polynomial of degree < n through n points (Lagrange interpolation, computed
+    /// via Newton's divided differences). None if two points have the same x.
+    pub fn interpolate<X: Into<Rational>, Y: Into<Rational>>(
+        points: impl IntoIterator<Item = (X, Y)>,
+    ) -> Option<Self> {
+        let (xs, mut divided): (Vec<Rational>, Vec<Rational>) =
+            points.into_iter().map(|(x, y)| (x.into(), y.into())).unzip();
+
+        // After round k, divided[i] = f[x_(i-k), ..., x_i] for all i >= k
+        for k in 1..xs.len() {
+            for i in (k..xs.len()).rev() {
+                let dx = xs[i] - xs[i - k];
+                if dx.is_zero() {
+                    return None;
+                }
+                divided[i] = (divided[i] - divided[i - 1]) / dx;
+            }
+        }
+        Some(Self::from_newton(&divided, &xs))
+    }
+
+    /// Like `interpolate`, but None unless the points lie on a polynomial of at most the given
+    /// degree. Use more than `max_degree + 1` points to actually check that.
+    pub fn fit<X: Into<Rational>, Y: Into<Rational>>(
+        points: impl IntoIterator<Item = (X, Y)>,
+        max_degree: usize,
+    ) -> Option<Self> {
+        Self::interpolate(points).filter(|polynomial| polynomial.degree() <= max_degree)
+    }
+
+    /// Expands c_0 + c_1 (x - x_0) + c_2 (x - x_0)(x - x_1) + ... with Horner's scheme
+    fn from_newton(coefficients: &[Rational], nodes: &[Rational]) -> Self {
+        let mut result = Vec::<Rational>::new();
+        for (c, node) in coefficients.iter().zip(nodes).rev() {
+            // result = result * (x - node) + c
+            result.insert(0, Rational::zero());
+            for i in 0..result.len() - 1 {
+                let shifted = result[i + 1] * *node;
+                result[i] -= shifted;
+            }
+            result[0] += c;
+        }
+        Self::new(result)
+    }
+
+    pub fn coefficients(&self) -> &[Rational] {
+        &self.coefficients
+    }
+
+    /// 0 for constant polynomials, including the zero polynomial
+    pub fn degree(&self) -> usize {
+        self.coefficients.len().saturating_sub(1)
+    }
+
+    pub fn eval(&self, x: impl Into<Rational>) -> Rational {
+        let x = x.into();
+        self.coefficients.iter().rev().fold(Rational::zero(), |acc, &c| acc * x + c)
+    }
+
+    /// Evaluates at x and returns the result if it is an integer that fits into an i64
+    pub fn eval_integer(&self, x: i64) -> Option<i64> {
+        let value = self.eval(x as i128);
+        value.is_integer().then(|| value.to_integer()).and_then(|v| i64::try_from(v).ok())
+    }
+}
+
+/// Forward differences of the equally spaced samples f(0), f(1), ..., f(n-1), which determine
+/// f at every integer via Newton's forward difference formula
+/// f(x) = sum_k binomial(x, k) * Δ^k f(0).
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ForwardDifferences {
+    /// Δ^k f(0) for k = 0, 1, ...
+    leading: Vec<i128>,
+    /// Set if some row of differences was constant over at least two entries
+    degree: Option<usize>,
+}
+
+impl ForwardDifferences {
+    pub fn new(values: &[i64]) -> Self {
+        let mut row = values.iter().map(|&v| v as i128).collect_vec();
+        let mut leading = Vec::new();
+        let mut degree = None;
+        while let Some(&first) = row.first() {
+            leading.push(first);
+            if row.len() >= 2 && row.iter().all_equal() {
+                degree = Some(leading.len() - 1);
+                break;
+            }
+            row = row.iter().tuple_windows().map(|(a, b)| b - a).collect();
+        }
+        Self { leading, degree }
+    }
+
+    /// The degree of the polynomial that the samples come from, if some row of the difference
+    /// table became constant. None if there were too few samples to tell.
+    pub fn degree(&self) -> Option<usize> {
+        self.degree
+    }
+
+    /// Δ^k f(0) for k = 0, 1, ..., up to the first constant difference if there is one
+    pub fn leading(&self) -> &[i128] {
+        &self.leading
+    }
+
+    /// f(x) for any integer x, including negative and huge ones. If the degree is unknown, this
+    /// is the polynomial of degree < n through all samples.
+    pub fn at(&self, x: i64) -> i64 {
+        let x = x as i128;
+        // binomial(x, k), which stays an integer even for negative x
+        let mut binomial = 1_i128;
+        let mut result = 0_i128;
+        for (k, &difference) in self.leading.iter().enumerate() {
+            if k > 0 {
+                binomial = binomial * (x - k as i128 + 1) / k as i128;
+            }
+            result += binomial * difference;
+        }
+        result.try_into().expect("result doesn't fit into an i64")
+    }
+
+    /// The same polynomial with exact coefficients
+    pub fn polynomial(&self) -> Polynomial {
+        // Δ^k f(0) / k! are the Newton coefficients for the nodes 0, 1, 2, ...
+        let mut factorial = Rational::one();
+        let coefficients = self
+            .leading
+            .iter()
+            .enumerate()
+            .map(|(k, &difference)| {
+                if k > 0 {
+                    factorial *= k as i128;
+                }
+                Rational::from_integer(difference) / factorial
+            })
+            .collect_vec();
+        let nodes =
+            (0..coefficients.len()).map(|i| Rational::from_integer(i as i128)).collect_vec();
+        Polynomial::from_newton(&coefficients, &nodes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpolate() {
+        // 2x^2 - 3x + 1
+        let f = |x: i64| 2 * x * x - 3 * x + 1;
+        let points = [-2, 0, 3, 7].map(|x| (x as i128, f(x) as i128));
+        let polynomial = Polynomial::interpolate(points).unwrap();
+        assert_eq!(polynomial, Polynomial::new([1_i128, -3, 2]));
+        assert_eq!(polynomial.degree(), 2);
+        assert_eq!(polynomial.eval_integer(1_000_000), Some(f(1_000_000)));
+
+        assert_eq!(Polynomial::fit(points, 2), Some(polynomial.clone()));
+        assert_eq!(Polynomial::fit(points, 1), None);
+        assert_eq!(Polynomial::interpolate([(1_i128, 2_i128), (1, 3)]), None);
+
+        // Through (0, 0) and (2, 1) we need rational coefficients
+        let line = Polynomial::interpolate([(0_i128, 0_i128), (2, 1)]).unwrap();
+        assert_eq!(line.eval(1_i128), Rational::new(1, 2));
+        assert_eq!(line.eval_integer(1), None);
+
+        // x^2 overflows an i64 at 2^32
+        let square = Polynomial::interpolate([(0_i128, 0_i128), (1, 1), (2, 4)]).unwrap();
+        assert_eq!(square.eval_integer(1 << 31), Some(1 << 62));
+        assert_eq!(square.eval_integer(1 << 32), None);
+
+        assert_eq!(
+            Polynomial::interpolate(Vec::<(i128, i128)>::new()),
+            Some(Polynomial::default())
+        );
+        assert_eq!(Polynomial::default().degree(), 0);
+    }
+
+    #[test]
+    fn test_forward_differences() {
+        let differences = ForwardDifferences::new(&[1, 3, 6, 10, 15, 21]);
+        assert_eq!(differences.degree(), Some(2));
+        assert_eq!(differences.leading(), &[1, 2, 1]);
+        assert_eq!(differences.at(6), 28);
+        assert_eq!(differences.at(-1), 0);
+        assert_eq!(differences.at(-3), 1);
+
+        // Cubic, evaluated far away
+        let f = |x: i64| x * x * x - 5 * x + 7;
+        let differences = ForwardDifferences::new(&(0..6).map(f).collect_vec());
+        assert_eq!(differences.degree(), Some(3));
+        assert_eq!(differences.at(1_000_000), f(1_000_000));
+        assert_eq!(differences.at(-12345), f(-12345));
+        assert_eq!(differences.polynomial(), Polynomial::new([7_i128, -5, 0, 1]));
+
+        // A constant sequence has degree 0, an empty one is zero everywhere
+        assert_eq!(ForwardDifferences::new(&[4, 4]).degree(), Some(0));
+        assert_eq!(ForwardDifferences::new(&[4, 4]).at(100), 4);
+        assert_eq!(ForwardDifferences::new(&[]).at(3), 0);
+
+        // Too short to see a constant row, but still the interpolating polynomial
+        let short = ForwardDifferences::new(&[1, 2, 4]);
+        assert_eq!(short.degree(), None);
+        assert_eq!(short.at(3), 7);
+    }
+}
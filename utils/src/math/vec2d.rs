@@ -1,37 +1,54 @@
+use num::{NumCast, Signed, ToPrimitive};
 use parse_display::FromStr;
 
-#[derive(Copy, Clone, Debug, Eq, Hash, FromStr, PartialOrd, Ord)]
-// Parses [1, 2] or (1, 2) or {1, 2}
-#[from_str(regex = r"[\[\(\{](?<x>-?\d+),\s*(?<y>-?\d+)[\]\)\}]")]
-pub struct Vec2D {
-    pub x: i64,
-    pub y: i64,
+use crate::math::Neighborhood;
+
+/// A 2D vector, i64 by default. Other coordinate types (f64 for trajectories, i128 for huge
+/// areas) get the same arithmetics, but the constructors and neighbor functions only exist for
+/// i64, so that e.g. `Vec2D::new(1, 2)` or `Vec2D::zero()` never needs a type annotation.
+/// Use the public fields, `From` or `cast` to create the other ones.
+#[derive(Copy, Clone, Debug, Hash, FromStr)]
+// Parses [1, 2] or (1, 2) or {1, 2}, as well as (1.5, -2.0) for floats
+#[from_str(regex = r"[\[\(\{](?<x>-?\d+(\.\d+)?),\s*(?<y>-?\d+(\.\d+)?)[\]\)\}]")]
+pub struct Vec2D<T = i64> {
+    pub x: T,
+    pub y: T,
 }
 
-impl<T> PartialEq<T> for Vec2D where T: Into<Vec2D> + Copy {
-    fn eq(&self, other: &T) -> bool {
-        let other: Vec2D = (*other).into();
+impl<T, U> PartialEq<U> for Vec2D<T>
+where
+    T: PartialEq + Copy,
+    U: Into<Vec2D<T>> + Copy,
+{
+    fn eq(&self, other: &U) -> bool {
+        let other: Vec2D<T> = (*other).into();
         self.x == other.x && self.y == other.y
     }
 }
 
+impl<T: Eq + Copy> Eq for Vec2D<T> {}
+
+impl<T: PartialOrd + Copy> PartialOrd for Vec2D<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.xy_tuple().partial_cmp(&other.xy_tuple())
+    }
+}
+
+impl<T: Ord + Copy> Ord for Vec2D<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.xy_tuple().cmp(&other.xy_tuple())
+    }
+}
+
 impl Vec2D {
     pub fn new(x: i64, y: i64) -> Self {
         Self { x, y }
     }
 
-    pub fn xy_tuple(&self) -> (i64, i64) {
-        (self.x, self.y)
-    }
-
     pub fn zero() -> Self {
         Self::new(0, 0)
     }
 
-    pub fn is_zero(&self) -> bool {
-        *self == Self::zero()
-    }
-
     pub fn xaxis(x: i64) -> Self {
         Self { x, y: 0 }
     }
@@ -40,106 +57,175 @@ impl Vec2D {
         Self { x: 0, y }
     }
 
-    pub fn abs(&self) -> Self {
-        Self::new(self.x.abs(), self.y.abs())
+    pub fn neighbors_in<'a>(
+        &self,
+        neighborhood: &'a Neighborhood,
+    ) -> impl Iterator<Item = Vec2D> + 'a {
+        neighborhood.neighbors_of(*self)
+    }
+
+    pub fn diagonal_neighbors(&self) -> impl Iterator<Item = Vec2D> + '_ {
+        self.neighbors_in(&Neighborhood::Diagonal)
+    }
+
+    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item = Vec2D> + '_ {
+        self.neighbors_in(&Neighborhood::VonNeumann)
+    }
+
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Vec2D> + '_ {
+        self.neighbors_in(&Neighborhood::Moore)
+    }
+}
+
+impl<T: Copy> Vec2D<T> {
+    pub fn xy_tuple(&self) -> (T, T) {
+        (self.x, self.y)
     }
 
     pub fn flipped(&self) -> Self {
-        Self::new(self.y, self.x)
+        Self { x: self.y, y: self.x }
     }
 
-    pub fn rotated_right(&self) -> Self {
-        Self::new(-self.y, self.x)
+    /// Converts to another coordinate type, None if a coordinate does not fit or would lose its
+    /// fractional part (e.g. 1.5 to an integer)
+    pub fn cast<U: NumCast>(&self) -> Option<Vec2D<U>>
+    where
+        T: ToPrimitive,
+    {
+        Some(Vec2D { x: exact_cast(self.x)?, y: exact_cast(self.y)? })
     }
 
-    pub fn rotated_left(&self) -> Self {
-        Self::new(self.y, -self.x)
+    /// Same as `From<(A, B)>`, but returns None instead of panicking if a coordinate does not fit
+    /// or would lose its fractional part
+    pub fn checked_from<A: ToPrimitive, B: ToPrimitive>((x, y): (A, B)) -> Option<Self>
+    where
+        T: NumCast,
+    {
+        Some(Self { x: exact_cast(x)?, y: exact_cast(y)? })
     }
+}
 
-    pub fn manhattan_dist(&self) -> i64 {
-        self.x.abs() + self.y.abs()
+/// NumCast truncates floats when converting them to integers, this returns None instead. NaN
+/// never converts.
+pub(crate) fn exact_cast<S: ToPrimitive, U: NumCast>(value: S) -> Option<U> {
+    let expected = value.to_f64();
+    let result = U::from(value)?;
+    (result.to_f64() == expected).then_some(result)
+}
+
+impl<T: num::Num + Copy> Vec2D<T> {
+    pub fn is_zero(&self) -> bool {
+        self.x.is_zero() && self.y.is_zero()
+    }
+
+    /// Multiplies both coordinates with a factor of the coordinate type, e.g. a float
+    pub fn scaled(&self, factor: T) -> Self {
+        Self { x: self.x * factor, y: self.y * factor }
     }
 
-    pub fn both_coordinates_positive(&self) -> bool {
-        self.x >= 0 && self.y >= 0
+    pub fn dot(&self, other: impl Into<Self>) -> T {
+        let other = other.into();
+        self.x * other.x + self.y * other.y
     }
 
-    pub fn inside_box(&self, lower: impl Into<Vec2D>, upper: impl Into<Vec2D>) -> bool {
+    /// z coordinate of the 3D cross product, i.e. the signed area of the parallelogram spanned by
+    /// both vectors. Positive if other is clockwise of self in our y-down coordinates.
+    pub fn cross(&self, other: impl Into<Self>) -> T {
+        let other = other.into();
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Euclidean length
+    pub fn length(&self) -> f64
+    where
+        T: ToPrimitive,
+    {
+        let (x, y) = (self.x.to_f64().unwrap(), self.y.to_f64().unwrap());
+        x.hypot(y)
+    }
+
+    pub fn both_coordinates_positive(&self) -> bool
+    where
+        T: PartialOrd,
+    {
+        self.x >= T::zero() && self.y >= T::zero()
+    }
+
+    pub fn inside_box(&self, lower: impl Into<Self>, upper: impl Into<Self>) -> bool
+    where
+        T: PartialOrd,
+    {
         let (lower, upper) = (lower.into(), upper.into());
         self.x >= lower.x && self.y >= lower.y && self.x <= upper.x && self.y <= upper.y
     }
+}
 
-    pub fn diagonal_neighbors(&self) -> impl Iterator<Item = Vec2D> + '_ {
-        [
-            Vec2D { x: 1, y: 1 },
-            Vec2D { x: -1, y: 1 },
-            Vec2D { x: 1, y: -1 },
-            Vec2D { x: -1, y: -1 },
-        ]
-        .iter()
-        .map(move |&dir| *self + dir)
+impl<T: Signed + Copy> Vec2D<T> {
+    pub fn abs(&self) -> Self {
+        Self { x: self.x.abs(), y: self.y.abs() }
     }
 
-    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item = Vec2D> + '_ {
-        [
-            Vec2D { x: 1, y: 0 },
-            Vec2D { x: -1, y: 0 },
-            Vec2D { x: 0, y: 1 },
-            Vec2D { x: 0, y: -1 },
-        ]
-        .iter()
-        .map(move |&dir| *self + dir)
+    pub fn rotated_right(&self) -> Self {
+        Self { x: -self.y, y: self.x }
     }
 
-    pub fn all_neighbors(&self) -> impl Iterator<Item = Vec2D> + '_ {
-        self.diagonal_neighbors().chain(self.orthogonal_neighbors())
+    pub fn rotated_left(&self) -> Self {
+        Self { x: self.y, y: -self.x }
+    }
+
+    pub fn manhattan_dist(&self) -> T {
+        self.x.abs() + self.y.abs()
     }
 }
 
 /// This would of course be horrible in productions, but it make the Advent of Code solutions much
-/// more readable. We assume that we never need any positions whose coordinates are out of i64
-/// bounds.
-impl<T, U> From<(T, U)> for Vec2D
+/// more readable. We assume that we never need any positions whose coordinates are out of bounds
+/// of the coordinate type, use `checked_from` if that is not the case.
+impl<T, A, B> From<(A, B)> for Vec2D<T>
 where
-    T: num::ToPrimitive,
-    U: num::ToPrimitive,
+    T: NumCast,
+    A: ToPrimitive,
+    B: ToPrimitive,
 {
-    fn from((x, y): (T, U)) -> Self {
-        Self::new(x.to_i64().unwrap(), y.to_i64().unwrap())
+    fn from((x, y): (A, B)) -> Self {
+        Self { x: T::from(x).unwrap(), y: T::from(y).unwrap() }
     }
 }
 
-impl<T, U> From<&(T, U)> for Vec2D
+impl<T, A, B> From<&(A, B)> for Vec2D<T>
 where
-    T: num::ToPrimitive + Copy,
-    U: num::ToPrimitive + Copy,
+    T: NumCast,
+    A: ToPrimitive + Copy,
+    B: ToPrimitive + Copy,
 {
-    fn from((x, y): &(T, U)) -> Self {
+    fn from((x, y): &(A, B)) -> Self {
         Self::from((*x, *y))
     }
 }
 
 /// Again, horrible in production, but nice for AoC.
 /// This way we don't need to manually convert before multiplying.
-impl<T> std::ops::Mul<T> for Vec2D
+/// Only integer factors, so that `Vec2D::new(1, 2) * 1.5` doesn't compile instead of
+/// truncating the factor. Use `scaled` to multiply float vectors by a float.
+impl<T, S> std::ops::Mul<S> for Vec2D<T>
 where
-    T: num::ToPrimitive + num::Integer,
+    T: NumCast + std::ops::Mul<Output = T> + Copy,
+    S: ToPrimitive + num::Integer,
 {
-    type Output = Vec2D;
+    type Output = Vec2D<T>;
 
-    fn mul(self, rhs: T) -> Self::Output {
-        Vec2D::new(
-            self.x * rhs.to_i64().unwrap(),
-            self.y * rhs.to_i64().unwrap(),
-        )
+    fn mul(self, rhs: S) -> Self::Output {
+        let rhs = T::from(rhs).unwrap();
+        Vec2D { x: self.x * rhs, y: self.y * rhs }
     }
 }
 
-impl <T> std::ops::MulAssign<T> for Vec2D
+impl<T, S> std::ops::MulAssign<S> for Vec2D<T>
 where
-    T: num::ToPrimitive + num::Integer,
+    T: NumCast + std::ops::Mul<Output = T> + Copy,
+    S: ToPrimitive + num::Integer,
 {
-    fn mul_assign(&mut self, rhs: T) {
+    fn mul_assign(&mut self, rhs: S) {
         *self = *self * rhs;
     }
 }
@@ -149,44 +235,71 @@ where
 macro_rules! impl_left_mul {
     ($($t:ty),*) => {
         $(
-            impl std::ops::Mul<Vec2D> for $t {
-                type Output = Vec2D;
-            
-                fn mul(self, rhs: Vec2D) -> Self::Output {
+            impl<T> std::ops::Mul<Vec2D<T>> for $t
+            where
+                Vec2D<T>: std::ops::Mul<$t, Output = Vec2D<T>>,
+            {
+                type Output = Vec2D<T>;
+
+                fn mul(self, rhs: Vec2D<T>) -> Self::Output {
                     rhs * self
                 }
             }
         )*
     };
 }
-impl_left_mul!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_left_mul!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<T: std::ops::Neg<Output = T>> std::ops::Neg for Vec2D<T> {
+    type Output = Vec2D<T>;
+
+    fn neg(self) -> Self::Output {
+        Vec2D { x: -self.x, y: -self.y }
+    }
+}
 
-impl<T> std::ops::Add<T> for Vec2D where T: Into<Vec2D> {
-    type Output = Vec2D;
+impl<T, U> std::ops::Add<U> for Vec2D<T>
+where
+    T: std::ops::Add<Output = T>,
+    U: Into<Vec2D<T>>,
+{
+    type Output = Vec2D<T>;
 
-    fn add(self, rhs: T) -> Self::Output {
+    fn add(self, rhs: U) -> Self::Output {
         let rhs = rhs.into();
-        Vec2D::new(self.x + rhs.x, self.y + rhs.y)
+        Vec2D { x: self.x + rhs.x, y: self.y + rhs.y }
     }
 }
 
-impl<T> std::ops::AddAssign<T> for Vec2D where T: Into<Vec2D> {
-    fn add_assign(&mut self, rhs: T) {
+impl<T, U> std::ops::AddAssign<U> for Vec2D<T>
+where
+    T: std::ops::Add<Output = T> + Copy,
+    U: Into<Vec2D<T>>,
+{
+    fn add_assign(&mut self, rhs: U) {
         *self = *self + rhs;
     }
 }
 
-impl<T> std::ops::Sub<T> for Vec2D where T: Into<Vec2D> {
-    type Output = Vec2D;
+impl<T, U> std::ops::Sub<U> for Vec2D<T>
+where
+    T: std::ops::Sub<Output = T>,
+    U: Into<Vec2D<T>>,
+{
+    type Output = Vec2D<T>;
 
-    fn sub(self, rhs: T) -> Self::Output {
+    fn sub(self, rhs: U) -> Self::Output {
         let rhs = rhs.into();
-        Vec2D::new(self.x - rhs.x, self.y - rhs.y)
+        Vec2D { x: self.x - rhs.x, y: self.y - rhs.y }
     }
 }
 
-impl<T> std::ops::SubAssign<T> for Vec2D where T: Into<Vec2D> {
-    fn sub_assign(&mut self, rhs: T) {
+impl<T, U> std::ops::SubAssign<U> for Vec2D<T>
+where
+    T: std::ops::Sub<Output = T> + Copy,
+    U: Into<Vec2D<T>>,
+{
+    fn sub_assign(&mut self, rhs: U) {
         *self = *self - rhs;
     }
 }
@@ -306,4 +419,46 @@ mod tests {
             Vec2D::new(1, 2).all_neighbors().collect(),
         );
     }
+
+    #[test]
+    fn generic_coordinates() {
+        let mut vec: Vec2D<f64> = Vec2D::from((0.5, 1));
+        vec += (1, 1);
+        assert_eq!(vec, (1.5, 2.0));
+        assert_eq!(vec * 2, (3.0, 4.0));
+        assert_eq!(vec.scaled(0.5), (0.75, 1.0));
+        assert_eq!((vec * 2).length(), 5.0);
+        assert_eq!("(1.5, -2.25)".parse(), Ok(Vec2D { x: 1.5, y: -2.25 }));
+
+        let huge = Vec2D::<i128> { x: i64::MAX as i128, y: 2 } * 4;
+        assert_eq!(huge.manhattan_dist(), i64::MAX as i128 * 4 + 8);
+        assert_eq!(huge.cast::<i64>(), None);
+        assert_eq!(Vec2D::<i128>::from((1, 2)).cast::<i64>(), Some(Vec2D::new(1, 2)));
+        assert_eq!(Vec2D { x: 3.0, y: -1.0 }.cast::<i64>(), Some(Vec2D::new(3, -1)));
+        assert_eq!(Vec2D { x: 3.0, y: -1.5 }.cast::<i64>(), None);
+    }
+
+    #[test]
+    fn checked_from() {
+        assert_eq!(Vec2D::<i64>::checked_from((1_u8, 2_usize)), Some(Vec2D::new(1, 2)));
+        assert_eq!(Vec2D::<i64>::checked_from((u64::MAX, 0)), None);
+        assert_eq!(Vec2D::<u8>::checked_from((-1, 0)), None);
+        assert_eq!(Vec2D::<i64>::checked_from((1.0, -2.0)), Some(Vec2D::new(1, -2)));
+        assert_eq!(Vec2D::<i64>::checked_from((1.5, 0)), None);
+        assert_eq!(Vec2D::<i64>::checked_from((0, f64::NAN)), None);
+        assert_eq!(Vec2D::<i64>::checked_from((f64::INFINITY, 0)), None);
+        assert_eq!(Vec2D::<f64>::checked_from((1, 2.5)), Some(Vec2D { x: 1.0, y: 2.5 }));
+    }
+
+    #[test]
+    fn dot_and_cross() {
+        let (a, b) = (Vec2D::new(1, 2), Vec2D::new(3, -4));
+        assert_eq!(a.dot(b), -5);
+        assert_eq!(a.cross(b), -10);
+        assert_eq!(b.cross(a), 10);
+        assert_eq!(a.cross(a * 3), 0);
+        assert_eq!(Vec2D::xaxis(1).cross(Vec2D::yaxis(1)), 1);
+        assert_eq!(a.dot(a.rotated_right()), 0);
+        assert_eq!(Vec2D::new(3, 4).length(), 5.0);
+    }
 }
@@ -0,0 +1,331 @@
+use num::{NumCast, ToPrimitive};
+use parse_display::FromStr;
+
+use crate::math::{exact_cast, Vec2D};
+
+/// A 3D vector, i64 by default. As for Vec2D, the constructors and neighbor functions only
+/// exist for i64, while other coordinate types (e.g. i128 to multiply large coordinates) get the
+/// arithmetics, `dot` and `cross`.
+#[derive(Copy, Clone, Debug, Hash, FromStr)]
+// Parses 1,2,3 or [1, 2, 3] or (1, 2, 3) or {1, 2, 3}
+#[from_str(regex = r"[\[\(\{]?\s*(?<x>-?\d+),\s*(?<y>-?\d+),\s*(?<z>-?\d+)\s*[\]\)\}]?")]
+pub struct Vec3D<T = i64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T, U> PartialEq<U> for Vec3D<T>
+where
+    T: PartialEq + Copy,
+    U: Into<Vec3D<T>> + Copy,
+{
+    fn eq(&self, other: &U) -> bool {
+        let other: Vec3D<T> = (*other).into();
+        self.x == other.x && self.y == other.y && self.z == other.z
+    }
+}
+
+impl<T: Eq + Copy> Eq for Vec3D<T> {}
+
+impl<T: PartialOrd + Copy> PartialOrd for Vec3D<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.xyz_tuple().partial_cmp(&other.xyz_tuple())
+    }
+}
+
+impl<T: Ord + Copy> Ord for Vec3D<T> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.xyz_tuple().cmp(&other.xyz_tuple())
+    }
+}
+
+impl Vec3D {
+    pub fn new(x: i64, y: i64, z: i64) -> Self {
+        Self { x, y, z }
+    }
+
+    /// Projection onto the xy plane
+    pub fn xy(&self) -> Vec2D {
+        Vec2D::new(self.x, self.y)
+    }
+
+    pub fn zero() -> Self {
+        Self::new(0, 0, 0)
+    }
+
+    pub fn xaxis(x: i64) -> Self {
+        Self { x, y: 0, z: 0 }
+    }
+
+    pub fn yaxis(y: i64) -> Self {
+        Self { x: 0, y, z: 0 }
+    }
+
+    pub fn zaxis(z: i64) -> Self {
+        Self { x: 0, y: 0, z }
+    }
+
+    pub fn abs(&self) -> Self {
+        Self::new(self.x.abs(), self.y.abs(), self.z.abs())
+    }
+
+    pub fn manhattan_dist(&self) -> i64 {
+        self.x.abs() + self.y.abs() + self.z.abs()
+    }
+
+    /// The 6 neighbors that share a face
+    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item = Vec3D> + '_ {
+        [
+            Vec3D { x: 1, y: 0, z: 0 },
+            Vec3D { x: -1, y: 0, z: 0 },
+            Vec3D { x: 0, y: 1, z: 0 },
+            Vec3D { x: 0, y: -1, z: 0 },
+            Vec3D { x: 0, y: 0, z: 1 },
+            Vec3D { x: 0, y: 0, z: -1 },
+        ]
+        .iter()
+        .map(move |&dir| *self + dir)
+    }
+
+    /// All 26 neighbors
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Vec3D> + '_ {
+        (-1..=1)
+            .flat_map(|x| (-1..=1).flat_map(move |y| (-1..=1).map(move |z| Vec3D::new(x, y, z))))
+            .filter(|dir| !dir.is_zero())
+            .map(move |dir| *self + dir)
+    }
+}
+
+impl<T: Copy> Vec3D<T> {
+    pub fn xyz_tuple(&self) -> (T, T, T) {
+        (self.x, self.y, self.z)
+    }
+
+    /// Converts to another coordinate type, None if a coordinate does not fit or would lose its
+    /// fractional part
+    pub fn cast<U: NumCast>(&self) -> Option<Vec3D<U>>
+    where
+        T: ToPrimitive,
+    {
+        Some(Vec3D { x: exact_cast(self.x)?, y: exact_cast(self.y)?, z: exact_cast(self.z)? })
+    }
+}
+
+impl<T: num::Num + Copy> Vec3D<T> {
+    pub fn is_zero(&self) -> bool {
+        self.x.is_zero() && self.y.is_zero() && self.z.is_zero()
+    }
+
+    pub fn dot(&self, other: impl Into<Self>) -> T {
+        let other = other.into();
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Perpendicular to both vectors, zero if they are parallel
+    pub fn cross(&self, other: impl Into<Self>) -> Self {
+        let other = other.into();
+        Self {
+            x: self.y * other.z - self.z * other.y,
+            y: self.z * other.x - self.x * other.z,
+            z: self.x * other.y - self.y * other.x,
+        }
+    }
+}
+
+/// Same as for Vec2D, we assume that we never need any positions whose coordinates are out of
+/// i64 bounds.
+impl<T, A, B, C> From<(A, B, C)> for Vec3D<T>
+where
+    T: NumCast,
+    A: ToPrimitive,
+    B: ToPrimitive,
+    C: ToPrimitive,
+{
+    fn from((x, y, z): (A, B, C)) -> Self {
+        Self { x: T::from(x).unwrap(), y: T::from(y).unwrap(), z: T::from(z).unwrap() }
+    }
+}
+
+impl<T, A, B, C> From<&(A, B, C)> for Vec3D<T>
+where
+    T: NumCast,
+    A: ToPrimitive + Copy,
+    B: ToPrimitive + Copy,
+    C: ToPrimitive + Copy,
+{
+    fn from((x, y, z): &(A, B, C)) -> Self {
+        Self::from((*x, *y, *z))
+    }
+}
+
+/// Like for Vec2D, only integer factors, converted to the coordinate type
+impl<T, S> std::ops::Mul<S> for Vec3D<T>
+where
+    T: NumCast + std::ops::Mul<Output = T> + Copy,
+    S: ToPrimitive + num::Integer,
+{
+    type Output = Vec3D<T>;
+
+    fn mul(self, rhs: S) -> Self::Output {
+        let rhs = T::from(rhs).unwrap();
+        Vec3D { x: self.x * rhs, y: self.y * rhs, z: self.z * rhs }
+    }
+}
+
+impl<T, S> std::ops::MulAssign<S> for Vec3D<T>
+where
+    T: NumCast + std::ops::Mul<Output = T> + Copy,
+    S: ToPrimitive + num::Integer,
+{
+    fn mul_assign(&mut self, rhs: S) {
+        *self = *self * rhs;
+    }
+}
+
+macro_rules! impl_left_mul {
+    ($($t:ty),*) => {
+        $(
+            impl<T> std::ops::Mul<Vec3D<T>> for $t
+            where
+                Vec3D<T>: std::ops::Mul<$t, Output = Vec3D<T>>,
+            {
+                type Output = Vec3D<T>;
+
+                fn mul(self, rhs: Vec3D<T>) -> Self::Output {
+                    rhs * self
+                }
+            }
+        )*
+    };
+}
+impl_left_mul!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+impl<T: std::ops::Neg<Output = T>> std::ops::Neg for Vec3D<T> {
+    type Output = Vec3D<T>;
+
+    fn neg(self) -> Self::Output {
+        Vec3D { x: -self.x, y: -self.y, z: -self.z }
+    }
+}
+
+impl<T, U> std::ops::Add<U> for Vec3D<T>
+where
+    T: std::ops::Add<Output = T>,
+    U: Into<Vec3D<T>>,
+{
+    type Output = Vec3D<T>;
+
+    fn add(self, rhs: U) -> Self::Output {
+        let rhs = rhs.into();
+        Vec3D { x: self.x + rhs.x, y: self.y + rhs.y, z: self.z + rhs.z }
+    }
+}
+
+impl<T, U> std::ops::AddAssign<U> for Vec3D<T>
+where
+    T: std::ops::Add<Output = T> + Copy,
+    U: Into<Vec3D<T>>,
+{
+    fn add_assign(&mut self, rhs: U) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T, U> std::ops::Sub<U> for Vec3D<T>
+where
+    T: std::ops::Sub<Output = T>,
+    U: Into<Vec3D<T>>,
+{
+    type Output = Vec3D<T>;
+
+    fn sub(self, rhs: U) -> Self::Output {
+        let rhs = rhs.into();
+        Vec3D { x: self.x - rhs.x, y: self.y - rhs.y, z: self.z - rhs.z }
+    }
+}
+
+impl<T, U> std::ops::SubAssign<U> for Vec3D<T>
+where
+    T: std::ops::Sub<Output = T> + Copy,
+    U: Into<Vec3D<T>>,
+{
+    fn sub_assign(&mut self, rhs: U) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn arithmetics() {
+        let mut vec = Vec3D::zero() + (-1, 1, 2);
+        assert_eq!(vec, (-1, 1, 2));
+        assert_eq!(vec.xyz_tuple(), (-1, 1, 2));
+
+        vec += (1, 2, 3);
+        assert_eq!(vec, (0, 3, 5));
+
+        vec -= (1, 2, 3);
+        assert_eq!(vec, (-1, 1, 2));
+
+        assert_eq!(-vec, (1, -1, -2));
+        assert_eq!(vec - vec, Vec3D::zero());
+
+        vec = Vec3D::new(1, 2, 3) * 2;
+        assert_eq!(vec, (2, 4, 6));
+        vec *= -2;
+        assert_eq!(vec, (-4, -8, -12));
+        assert_eq!(2 * vec, (-8, -16, -24));
+
+        assert_eq!(vec.xy(), Vec2D::new(-4, -8));
+        assert_eq!(Vec3D::xaxis(1) + Vec3D::yaxis(2) + Vec3D::zaxis(3), (1, 2, 3));
+    }
+
+    #[test]
+    fn dot_and_cross() {
+        let (a, b) = (Vec3D::new(1, 2, 3), Vec3D::new(4, 5, 6));
+        assert_eq!(a.dot(b), 32);
+        assert_eq!(a.cross(b), (-3, 6, -3));
+        assert_eq!(a.cross(b).dot(a), 0);
+        assert!(a.cross(a * 2).is_zero());
+
+        // Would overflow in i64
+        let big = Vec3D::new(i64::MAX, 0, 0).cast::<i128>().unwrap();
+        assert_eq!(big.dot(big), i64::MAX as i128 * i64::MAX as i128);
+        assert_eq!(big * u64::MAX, Vec3D { x: i64::MAX as i128 * u64::MAX as i128, y: 0, z: 0 });
+        assert_eq!(2 * big, big + big);
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("1,2,3".parse(), Ok(Vec3D::new(1, 2, 3)));
+        assert_eq!("19, 13, -30".parse(), Ok(Vec3D::new(19, 13, -30)));
+        assert_eq!("(1, 2, 3)".parse(), Ok(Vec3D::new(1, 2, 3)));
+        assert_eq!("[-1,2,  3]".parse(), Ok(Vec3D::new(-1, 2, 3)));
+        assert!("1,2".parse::<Vec3D>().is_err());
+    }
+
+    #[test]
+    fn manhattan_dist() {
+        assert_eq!(Vec3D::new(1, -2, 3).manhattan_dist(), 6);
+        assert_eq!(Vec3D::new(1, -2, 3).abs(), (1, 2, 3));
+    }
+
+    #[test]
+    fn neighbors() {
+        let pos = Vec3D::new(1, 2, 3);
+        let orthogonal = pos.orthogonal_neighbors().collect::<HashSet<_>>();
+        assert_eq!(orthogonal.len(), 6);
+        assert!(orthogonal.iter().all(|n| (*n - pos).manhattan_dist() == 1));
+
+        let all = pos.all_neighbors().collect::<HashSet<_>>();
+        assert_eq!(all.len(), 26);
+        assert!(all.is_superset(&orthogonal));
+        assert!(!all.contains(&pos));
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    ops::{Index, IndexMut},
+    str::FromStr,
+};
+
+use itertools::Itertools;
+
+use crate::math::{Vec2D, Vec3D};
+
+/// Vector with an arbitrary (but fixed) number of dimensions, for when Vec2D and Vec3D are not
+/// enough.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct VecND<const N: usize>(pub [i64; N]);
+
+impl<const N: usize> Default for VecND<N> {
+    fn default() -> Self {
+        Self::zero()
+    }
+}
+
+impl<const N: usize> VecND<N> {
+    pub fn new(coords: [i64; N]) -> Self {
+        Self(coords)
+    }
+
+    pub fn zero() -> Self {
+        Self([0; N])
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&c| c == 0)
+    }
+
+    /// Unit vector along the given axis
+    pub fn axis(axis: usize, length: i64) -> Self {
+        let mut vec = Self::zero();
+        vec[axis] = length;
+        vec
+    }
+
+    pub fn abs(&self) -> Self {
+        Self(self.0.map(i64::abs))
+    }
+
+    pub fn manhattan_dist(&self) -> i64 {
+        self.0.iter().map(|c| c.abs()).sum()
+    }
+
+    /// The 2 * N neighbors along the axes
+    pub fn orthogonal_neighbors(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..N).flat_map(move |axis| [1, -1].map(|dir| *self + Self::axis(axis, dir)))
+    }
+
+    /// All 3^N - 1 neighbors
+    pub fn all_neighbors(&self) -> impl Iterator<Item = Self> + '_ {
+        (0..N)
+            .map(|_| -1..=1)
+            .multi_cartesian_product()
+            .map(|offset| Self(offset.try_into().unwrap()))
+            .filter(|offset| !offset.is_zero())
+            .map(move |offset| *self + offset)
+    }
+}
+
+impl<const N: usize> Index<usize> for VecND<N> {
+    type Output = i64;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<const N: usize> IndexMut<usize> for VecND<N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+/// Same as for Vec2D, we assume that we never need coordinates out of i64 bounds
+impl<T: num::ToPrimitive, const N: usize> From<[T; N]> for VecND<N> {
+    fn from(coords: [T; N]) -> Self {
+        Self(coords.map(|c| c.to_i64().unwrap()))
+    }
+}
+
+impl From<Vec2D> for VecND<2> {
+    fn from(vec: Vec2D) -> Self {
+        Self([vec.x, vec.y])
+    }
+}
+
+impl From<VecND<2>> for Vec2D {
+    fn from(VecND([x, y]): VecND<2>) -> Self {
+        Vec2D::new(x, y)
+    }
+}
+
+impl From<Vec3D> for VecND<3> {
+    fn from(vec: Vec3D) -> Self {
+        Self([vec.x, vec.y, vec.z])
+    }
+}
+
+impl From<VecND<3>> for Vec3D {
+    fn from(VecND([x, y, z]): VecND<3>) -> Self {
+        Vec3D::new(x, y, z)
+    }
+}
+
+/// Parses comma-separated coordinates, optionally surrounded by brackets
+impl<const N: usize> FromStr for VecND<N> {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let coords = s
+            .trim()
+            .trim_start_matches(['[', '(', '{'])
+            .trim_end_matches([']', ')', '}'])
+            .split(',')
+            .map(|c| c.trim().parse::<i64>().map_err(|e| format!("Failed to parse {c}: {e}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let num_coords = coords.len();
+        Ok(Self(
+            coords.try_into().map_err(|_| format!("Expected {N} coordinates, got {num_coords}"))?,
+        ))
+    }
+}
+
+impl<T, const N: usize> std::ops::Mul<T> for VecND<N>
+where
+    T: num::ToPrimitive + num::Integer,
+{
+    type Output = VecND<N>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        let rhs = rhs.to_i64().unwrap();
+        Self(self.0.map(|c| c * rhs))
+    }
+}
+
+impl<T, const N: usize> std::ops::MulAssign<T> for VecND<N>
+where
+    T: num::ToPrimitive + num::Integer,
+{
+    fn mul_assign(&mut self, rhs: T) {
+        *self = *self * rhs;
+    }
+}
+
+impl<const N: usize> std::ops::Neg for VecND<N> {
+    type Output = VecND<N>;
+
+    fn neg(self) -> Self::Output {
+        Self(self.0.map(|c| -c))
+    }
+}
+
+impl<T, const N: usize> std::ops::Add<T> for VecND<N>
+where
+    T: Into<VecND<N>>,
+{
+    type Output = VecND<N>;
+
+    fn add(mut self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        for axis in 0..N {
+            self[axis] += rhs[axis];
+        }
+        self
+    }
+}
+
+impl<T, const N: usize> std::ops::AddAssign<T> for VecND<N>
+where
+    T: Into<VecND<N>>,
+{
+    fn add_assign(&mut self, rhs: T) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T, const N: usize> std::ops::Sub<T> for VecND<N>
+where
+    T: Into<VecND<N>>,
+{
+    type Output = VecND<N>;
+
+    fn sub(mut self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+        for axis in 0..N {
+            self[axis] -= rhs[axis];
+        }
+        self
+    }
+}
+
+impl<T, const N: usize> std::ops::SubAssign<T> for VecND<N>
+where
+    T: Into<VecND<N>>,
+{
+    fn sub_assign(&mut self, rhs: T) {
+        *self = *self - rhs;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    #[test]
+    fn arithmetics() {
+        let mut vec = VecND::zero() + [1, 2, 3, 4];
+        assert_eq!(vec, VecND([1, 2, 3, 4]));
+
+        vec -= [1, 1, 1, 1];
+        assert_eq!(vec, VecND([0, 1, 2, 3]));
+
+        vec *= 2;
+        assert_eq!(vec, VecND([0, 2, 4, 6]));
+        assert_eq!(-vec, VecND([0, -2, -4, -6]));
+        assert_eq!((-vec).abs(), vec);
+        assert_eq!(vec.manhattan_dist(), 12);
+        assert_eq!(vec[3], 6);
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Vec2D::from(VecND::from(Vec2D::new(1, 2))), Vec2D::new(1, 2));
+        assert_eq!(Vec3D::from(VecND::from(Vec3D::new(1, 2, 3))), Vec3D::new(1, 2, 3));
+        assert_eq!(VecND::from([1_u8, 2_u8]), VecND([1, 2]));
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("1,2,3,4".parse(), Ok(VecND([1, 2, 3, 4])));
+        assert_eq!("(1, -2)".parse(), Ok(VecND([1, -2])));
+        assert_eq!("[ 1,2, 3 ]".parse(), Ok(VecND([1, 2, 3])));
+        assert!("1,2".parse::<VecND<3>>().is_err());
+        assert!("1,a".parse::<VecND<2>>().is_err());
+    }
+
+    #[test]
+    fn neighbors() {
+        let pos = VecND([1, 2, 3, 4]);
+        let orthogonal = pos.orthogonal_neighbors().collect::<HashSet<_>>();
+        assert_eq!(orthogonal.len(), 8);
+        assert!(orthogonal.iter().all(|n| (*n - pos).manhattan_dist() == 1));
+
+        let all = pos.all_neighbors().collect::<HashSet<_>>();
+        assert_eq!(all.len(), 80);
+        assert!(all.is_superset(&orthogonal));
+
+        assert_eq!(
+            VecND::from(Vec2D::new(1, 2)).all_neighbors().map(Vec2D::from).collect::<HashSet<_>>(),
+            Vec2D::new(1, 2).all_neighbors().collect()
+        );
+    }
+}
@@ -0,0 +1,127 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// How often a Memo could answer from its cache
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct MemoStats {
+    pub hits: usize,
+    pub misses: usize,
+}
+
+/// Cache for recursive functions. Create one per top-level call and pass it down the
+/// recursion: the keys can then borrow from that call's arguments, e.g. `(&[char], usize)`
+/// for suffixes of the input, so no key has to be allocated.
+///
+/// ```
+/// use utils::Memo;
+///
+/// fn fib(memo: &mut Memo<u64, u64>, n: u64) -> u64 {
+///     memo.get_or_insert_with(n, |memo| if n < 2 { n } else { fib(memo, n - 1) + fib(memo, n - 2) })
+/// }
+///
+/// let mut memo = Memo::new();
+/// assert_eq!(fib(&mut memo, 90), 2880067194370816120);
+/// assert_eq!(memo.stats().misses, 91);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: HashMap<K, V>,
+    stats: MemoStats,
+}
+
+impl<K, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self { cache: HashMap::new(), stats: MemoStats::default() }
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The cached value for `key`, or the result of `compute`, which gets the Memo back for
+    /// recursive calls. The cache is not borrowed while `compute` runs.
+    pub fn get_or_insert_with(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(value) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return value.clone();
+        }
+
+        self.stats.misses += 1;
+        let value = compute(self);
+        self.cache.insert(key, value.clone());
+        value
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// Number of cached values
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Forgets all cached values and resets the stats
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.stats = MemoStats::default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Number of ways to write the sum of `target` with parts from `parts` (in any order)
+    fn count_compositions<'a>(
+        memo: &mut Memo<(&'a [u64], u64), u64>,
+        parts: &'a [u64],
+        target: u64,
+    ) -> u64 {
+        memo.get_or_insert_with((parts, target), |memo| {
+            if target == 0 {
+                return 1;
+            }
+            parts
+                .iter()
+                .filter(|&&p| p <= target)
+                .map(|p| count_compositions(memo, parts, target - p))
+                .sum()
+        })
+    }
+
+    #[test]
+    fn borrowed_keys() {
+        let parts = vec![1, 2, 3];
+        let mut memo = Memo::new();
+        assert_eq!(count_compositions(&mut memo, &parts, 4), 7);
+        assert_eq!(count_compositions(&mut memo, &parts, 30), 53798080);
+        assert_eq!(memo.len(), 31);
+
+        // Equal slices are the same key, no matter where they live
+        let copy = parts.clone();
+        let before = memo.stats();
+        assert_eq!(count_compositions(&mut memo, &copy, 30), 53798080);
+        assert_eq!(memo.stats(), MemoStats { hits: before.hits + 1, ..before });
+    }
+
+    #[test]
+    fn stats_and_clear() {
+        let mut memo = Memo::new();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get_or_insert_with("a", |_| 1), 1);
+        assert_eq!(memo.get_or_insert_with("a", |_| unreachable!()), 1);
+        assert_eq!(memo.get_or_insert_with("b", |_| 2), 2);
+        assert_eq!(memo.stats(), MemoStats { hits: 1, misses: 2 });
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.stats(), MemoStats::default());
+        assert_eq!(memo.get_or_insert_with("a", |_| 3), 3);
+    }
+}
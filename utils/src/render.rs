@@ -0,0 +1,272 @@
+use std::{
+    collections::HashSet,
+    fmt::{Display, Write},
+};
+
+use itertools::Itertools;
+
+use crate::{
+    grid::Grid,
+    math::{Box2D, Vec2D},
+    sparse_grid::SparseGrid,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Backend {
+    /// Highlighted cells are replaced by the highlight char
+    #[default]
+    Plain,
+    /// Highlighted cells keep their char but are printed in bold red
+    Ansi,
+}
+
+const ANSI_HIGHLIGHT: &str = "\x1b[1;31m";
+const ANSI_RESET: &str = "\x1b[0m";
+
+type Lookup<'a, T> = Box<dyn Fn(&Vec2D) -> Option<&'a T> + 'a>;
+
+/// Builder for rendering a grid to a string, e.g. for debugging. Use `grid.render(formatter)` to
+/// create one and print it via its Display implementation.
+pub struct Renderer<'a, T> {
+    lookup: Lookup<'a, T>,
+    formatter: Box<dyn Fn(&T) -> char + 'a>,
+    bounds: Option<Box2D>,
+    background: char,
+    axis_labels: bool,
+    highlighted: HashSet<Vec2D>,
+    highlight_char: char,
+    backend: Backend,
+}
+
+impl<'a, T> Renderer<'a, T> {
+    /// `bounds` is None for an empty grid, in which case nothing is rendered
+    pub fn new(
+        lookup: impl Fn(&Vec2D) -> Option<&'a T> + 'a,
+        formatter: impl Fn(&T) -> char + 'a,
+        bounds: Option<Box2D>,
+    ) -> Self {
+        Self {
+            lookup: Box::new(lookup),
+            formatter: Box::new(formatter),
+            bounds,
+            background: '.',
+            axis_labels: false,
+            highlighted: HashSet::new(),
+            highlight_char: 'O',
+            backend: Backend::Plain,
+        }
+    }
+
+    /// Char for positions inside the bounds that have no cell (only relevant for sparse grids)
+    pub fn background(mut self, background: char) -> Self {
+        self.background = background;
+        self
+    }
+
+    pub fn bounds(mut self, bounds: Box2D) -> Self {
+        self.bounds = Some(bounds);
+        self
+    }
+
+    pub fn axis_labels(mut self) -> Self {
+        self.axis_labels = true;
+        self
+    }
+
+    pub fn highlight<P: Into<Vec2D>>(mut self, positions: impl IntoIterator<Item = P>) -> Self {
+        self.highlighted.extend(positions.into_iter().map(Into::into));
+        self
+    }
+
+    pub fn highlight_char(mut self, highlight_char: char) -> Self {
+        self.highlight_char = highlight_char;
+        self
+    }
+
+    pub fn backend(mut self, backend: Backend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn ansi(self) -> Self {
+        self.backend(Backend::Ansi)
+    }
+
+    fn write_cell(&self, f: &mut std::fmt::Formatter<'_>, pos: &Vec2D) -> std::fmt::Result {
+        let c = (self.lookup)(pos).map(|cell| (self.formatter)(cell)).unwrap_or(self.background);
+        if !self.highlighted.contains(pos) {
+            return f.write_char(c);
+        }
+        match self.backend {
+            Backend::Plain => f.write_char(self.highlight_char),
+            Backend::Ansi => write!(f, "{ANSI_HIGHLIGHT}{c}{ANSI_RESET}"),
+        }
+    }
+}
+
+impl<T> Display for Renderer<'_, T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(bounds) = &self.bounds else {
+            return Ok(());
+        };
+
+        let row_label_width = if self.axis_labels {
+            [bounds.lower.y, bounds.upper.y].iter().map(|y| y.to_string().len()).max().unwrap()
+        } else {
+            0
+        };
+
+        if self.axis_labels {
+            // Column labels are written vertically, one line per digit
+            let col_labels = (bounds.lower.x..=bounds.upper.x).map(|x| x.to_string()).collect_vec();
+            let label_height = col_labels.iter().map(String::len).max().unwrap();
+            for line in 0..label_height {
+                write!(f, "{:row_label_width$} ", "")?;
+                for label in &col_labels {
+                    let padding = label_height - label.len();
+                    f.write_char(if line < padding {
+                        ' '
+                    } else {
+                        label.as_bytes()[line - padding] as char
+                    })?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        for y in bounds.lower.y..=bounds.upper.y {
+            if self.axis_labels {
+                write!(f, "{y:>row_label_width$} ")?;
+            }
+            for x in bounds.lower.x..=bounds.upper.x {
+                self.write_cell(f, &Vec2D::new(x, y))?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn grid_bounds<T>(grid: &Grid<T>) -> Option<Box2D> {
+    (grid.num_rows() > 0 && grid.num_cols() > 0)
+        .then(|| Box2D::new((0, 0), (grid.num_cols() - 1, grid.num_rows() - 1)))
+}
+
+impl<T> Grid<T> {
+    pub fn render<'a>(&'a self, formatter: impl Fn(&T) -> char + 'a) -> Renderer<'a, T> {
+        Renderer::new(|pos| self.get(*pos), formatter, grid_bounds(self))
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// Bounds are computed from the positions in the grid
+    pub fn render<'a>(&'a self, formatter: impl Fn(&T) -> char + 'a) -> Renderer<'a, T> {
+        Renderer::new(|pos| self.get(*pos), formatter, self.bounds())
+    }
+}
+
+/// Writes the full Display output of every cell, right-aligned to the widest one. If that is
+/// wider than one char, cells are separated by spaces so that numbers stay readable. Missing
+/// cells are written as '.'.
+fn write_cells<'a, T: Display + 'a>(
+    f: &mut std::fmt::Formatter<'_>,
+    bounds: Option<Box2D>,
+    lookup: impl Fn(&Vec2D) -> Option<&'a T>,
+) -> std::fmt::Result {
+    let Some(bounds) = bounds else {
+        return Ok(());
+    };
+    let rows = (bounds.lower.y..=bounds.upper.y)
+        .map(|y| {
+            (bounds.lower.x..=bounds.upper.x)
+                .map(|x| lookup(&Vec2D::new(x, y)).map_or_else(|| ".".to_string(), T::to_string))
+                .collect_vec()
+        })
+        .collect_vec();
+
+    let width = rows.iter().flatten().map(|cell| cell.chars().count()).max().unwrap_or(0);
+    let separator = if width > 1 { " " } else { "" };
+    for row in rows {
+        writeln!(f, "{}", row.iter().map(|cell| format!("{cell:>width$}")).join(separator))?;
+    }
+    Ok(())
+}
+
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cells(f, grid_bounds(self), |pos| self.get(*pos))
+    }
+}
+
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write_cells(f, self.bounds(), |pos| self.get(*pos))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn display() {
+        let grid: Grid<_> = [[1, 2, 3], [4, 5, 6]].into();
+        assert_eq!(grid.to_string(), "123\n456\n");
+
+        let sparse: SparseGrid<_> = [((-1, 0), '#'), ((1, 1), '#')].into_iter().collect();
+        assert_eq!(sparse.to_string(), "#..\n..#\n");
+
+        assert_eq!(SparseGrid::<char>::new().to_string(), "");
+
+        // Wider cells are aligned instead of cut off
+        let grid: Grid<_> = [[1, 12], [100, 5]].into();
+        assert_eq!(grid.to_string(), "  1  12\n100   5\n");
+        let sparse: SparseGrid<_> = [((0, 0), 10), ((2, 0), 2)].into_iter().collect();
+        assert_eq!(sparse.to_string(), "10  .  2\n");
+    }
+
+    #[test]
+    fn formatter_and_background() {
+        let grid: Grid<_> = [[true, false], [false, true]].into();
+        assert_eq!(grid.render(|&b| if b { '#' } else { '.' }).to_string(), "#.\n.#\n");
+
+        let sparse: SparseGrid<_> = [((0, 0), 1), ((2, 0), 2)].into_iter().collect();
+        assert_eq!(sparse.render(|_| 'x').background(' ').to_string(), "x x\n");
+        assert_eq!(
+            sparse.render(|_| 'x').bounds(Box2D::new((0, -1), (3, 0))).to_string(),
+            "....\nx.x.\n"
+        );
+    }
+
+    #[test]
+    fn highlight() {
+        let grid: Grid<_> = [['.', '.', '.'], ['#', '#', '.']].into();
+        let path = [(0, 0), (1, 0), (2, 0), (2, 1)];
+
+        assert_eq!(grid.render(|&c| c).highlight(path).to_string(), "OOO\n##O\n");
+        assert_eq!(
+            grid.render(|&c| c).highlight(path).highlight_char('*').to_string(),
+            "***\n##*\n"
+        );
+        assert_eq!(
+            grid.render(|&c| c).highlight([(1, 1)]).ansi().to_string(),
+            "...\n#\x1b[1;31m#\x1b[0m.\n"
+        );
+    }
+
+    #[test]
+    fn axis_labels() {
+        let grid = Grid::with_value('.', 2, 12);
+        assert_eq!(
+            grid.render(|&c| c).axis_labels().to_string(),
+            ["            11", "  012345678901", "0 ............", "1 ............", ""].join("\n")
+        );
+
+        let sparse: SparseGrid<_> = [((-1, -10), '#'), ((1, -9), '#')].into_iter().collect();
+        assert_eq!(
+            sparse.render(|&c| c).axis_labels().to_string(),
+            ["    -  ", "    101", "-10 #..", " -9 ..#", ""].join("\n")
+        );
+    }
+}
@@ -1,46 +1,130 @@
 use std::{
-    collections::HashMap,
-    hash::Hash,
-    ops::{Deref, DerefMut},
+    collections::{BTreeMap, HashMap, LinkedList},
+    ops::{Index, IndexMut},
 };
 
-use crate::math::Vec2D;
+use rayon::prelude::*;
 
-#[derive(Debug, PartialEq, Eq, Clone, Default, derive_more::From)]
+use crate::{
+    graphs,
+    grid::{Grid, UnweightedGrid, WeightedGrid},
+    math::{Box2D, Neighborhood, Vec2D},
+};
+
+/// Grid where only some positions have a value. Lookups are O(1), iteration is in row-major
+/// order (sorting the positions each time, see `iter_unordered` for hot paths), and the
+/// bounding box of all positions is kept up to date on insert and remove.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct SparseGrid<T> {
     data: HashMap<Vec2D, T>,
+    // Number of positions per x and per y coordinate, the bounds are their first and last keys
+    xs: BTreeMap<i64, usize>,
+    ys: BTreeMap<i64, usize>,
+}
+
+/// Sorts by y first
+fn sort_row_major<V>(cells: &mut [(Vec2D, V)]) {
+    cells.sort_unstable_by_key(|(pos, _)| (pos.y, pos.x));
 }
 
-impl<T: Hash, V: Into<Vec2D>> FromIterator<(V, T)> for SparseGrid<T> {
+fn add_count(counts: &mut BTreeMap<i64, usize>, coordinate: i64) {
+    *counts.entry(coordinate).or_default() += 1;
+}
+
+fn remove_count(counts: &mut BTreeMap<i64, usize>, coordinate: i64) {
+    let count = counts.get_mut(&coordinate).unwrap();
+    *count -= 1;
+    if *count == 0 {
+        counts.remove(&coordinate);
+    }
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, V: Into<Vec2D>> FromIterator<(V, T)> for SparseGrid<T> {
     fn from_iter<I: IntoIterator<Item = (V, T)>>(iter: I) -> Self {
-        Self::from(
-            iter.into_iter()
-                .map(|(pos, val)| (pos.into(), val))
-                .collect::<HashMap<_,_>>(),
-        )
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
     }
 }
 
-impl<T> Deref for SparseGrid<T> {
-    type Target = HashMap<Vec2D, T>;
+impl<T, V: Into<Vec2D>> Extend<(V, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (V, T)>>(&mut self, iter: I) {
+        for (pos, val) in iter {
+            self.insert(pos, val);
+        }
+    }
+}
 
-    fn deref(&self) -> &Self::Target {
-        &self.data
+/// The cells are produced in parallel, inserting them (and updating the bounds) is sequential
+impl<T: Send, V: Into<Vec2D> + Send> ParallelExtend<(V, T)> for SparseGrid<T> {
+    fn par_extend<I: IntoParallelIterator<Item = (V, T)>>(&mut self, par_iter: I) {
+        let cells: LinkedList<Vec<_>> = par_iter.into_par_iter().collect_vec_list();
+        self.data.reserve(cells.iter().map(Vec::len).sum());
+        self.extend(cells.into_iter().flatten());
     }
 }
 
-impl<T> DerefMut for SparseGrid<T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.data
+pub struct IntoIter<T>(std::vec::IntoIter<(Vec2D, T)>);
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = (Vec2D, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for IntoIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.0.next_back()
+    }
+}
+
+impl<T> IntoIterator for SparseGrid<T> {
+    type Item = (Vec2D, T);
+    type IntoIter = IntoIter<T>;
+
+    /// Iterates in row-major order
+    fn into_iter(self) -> Self::IntoIter {
+        let mut cells = self.data.into_iter().collect::<Vec<_>>();
+        sort_row_major(&mut cells);
+        IntoIter(cells.into_iter())
+    }
+}
+
+impl<T, Pos> Index<Pos> for SparseGrid<T>
+where
+    Pos: Into<Vec2D>,
+{
+    type Output = T;
+
+    fn index(&self, pos: Pos) -> &Self::Output {
+        self.get(pos.into()).unwrap()
     }
 }
 
-impl<T> SparseGrid<T>
+impl<T, Pos> IndexMut<Pos> for SparseGrid<T>
 where
-    T: Hash,
+    Pos: Into<Vec2D>,
 {
+    fn index_mut(&mut self, pos: Pos) -> &mut Self::Output {
+        self.get_mut(pos.into()).unwrap()
+    }
+}
+
+impl<T> SparseGrid<T> {
     pub fn new() -> Self {
-        SparseGrid { data: HashMap::new() }
+        SparseGrid { data: HashMap::new(), xs: BTreeMap::new(), ys: BTreeMap::new() }
     }
 
     pub fn get(&self, pos: impl Into<Vec2D>) -> Option<&T> {
@@ -51,40 +135,193 @@ where
         self.data.get_mut(&pos.into())
     }
 
+    pub fn contains(&self, pos: &Vec2D) -> bool {
+        self.data.contains_key(pos)
+    }
+
+    pub fn insert(&mut self, pos: impl Into<Vec2D>, val: T) -> Option<T> {
+        let pos = pos.into();
+        let previous = self.data.insert(pos, val);
+        if previous.is_none() {
+            add_count(&mut self.xs, pos.x);
+            add_count(&mut self.ys, pos.y);
+        }
+        previous
+    }
+
+    pub fn remove(&mut self, pos: impl Into<Vec2D>) -> Option<T> {
+        let pos = pos.into();
+        let removed = self.data.remove(&pos);
+        if removed.is_some() {
+            remove_count(&mut self.xs, pos.x);
+            remove_count(&mut self.ys, pos.y);
+        }
+        removed
+    }
+
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.xs.clear();
+        self.ys.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Smallest box containing all positions, None if the grid is empty
+    pub fn bounds(&self) -> Option<Box2D> {
+        let (min_x, _) = self.xs.first_key_value()?;
+        let (max_x, _) = self.xs.last_key_value()?;
+        let (min_y, _) = self.ys.first_key_value()?;
+        let (max_y, _) = self.ys.last_key_value()?;
+        Some(Box2D::new((*min_x, *min_y), (*max_x, *max_y)))
+    }
+
+    /// Iterates in row-major order
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Vec2D, &T)> + '_ {
+        let mut cells = self.iter_unordered().collect::<Vec<_>>();
+        sort_row_major(&mut cells);
+        cells.into_iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl DoubleEndedIterator<Item = (Vec2D, &mut T)> + '_ {
+        let mut cells = self.data.iter_mut().map(|(pos, val)| (*pos, val)).collect::<Vec<_>>();
+        sort_row_major(&mut cells);
+        cells.into_iter()
+    }
+
+    /// Iterates in arbitrary order, without sorting
+    pub fn iter_unordered(&self) -> impl Iterator<Item = (Vec2D, &T)> + '_ {
+        self.data.iter().map(|(pos, val)| (*pos, val))
+    }
+
+    pub fn positions(&self) -> impl DoubleEndedIterator<Item = Vec2D> + '_ {
+        self.iter().map(|(pos, _)| pos)
+    }
+
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        self.iter().map(|(_, val)| val)
+    }
+
+    /// Dense grid covering `bounds()`, position p of the sparse grid ends up at
+    /// p - bounds().lower in the dense grid
+    pub fn to_option_grid(&self) -> Grid<Option<T>>
+    where
+        T: Clone,
+    {
+        self.to_dense_grid(|val| val.cloned())
+    }
+
+    /// Same as to_option_grid, but with `fill` for positions without a value
+    pub fn to_grid(&self, fill: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        self.to_dense_grid(|val| val.unwrap_or(&fill).clone())
+    }
+
+    fn to_dense_grid<U>(&self, mut f: impl FnMut(Option<&T>) -> U) -> Grid<U> {
+        let Some(bounds) = self.bounds() else {
+            return Grid::new(vec![]);
+        };
+        (bounds.lower.y..=bounds.upper.y)
+            .map(|y| {
+                (bounds.lower.x..=bounds.upper.x).map(|x| f(self.get((x, y)))).collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    pub fn neighbors_in<'a>(
+        &'a self,
+        pos: &Vec2D,
+        neighborhood: &'a Neighborhood,
+    ) -> impl Iterator<Item = (Vec2D, &'a T)> + 'a {
+        pos.neighbors_in(neighborhood).flat_map(|neighbor| Some((neighbor, self.get(neighbor)?)))
+    }
+
     pub fn orthogonal_neighbors<'a, 'b: 'a>(
         &'a self,
         pos: &'b Vec2D,
-    ) -> impl Iterator<Item = (&Vec2D, &T)> + 'a {
-        pos.orthogonal_neighbors()
-            .flat_map(|pos| self.get(pos))
-            .map(move |val| (pos, val))
+    ) -> impl Iterator<Item = (Vec2D, &'a T)> + 'a {
+        self.neighbors_in(pos, &Neighborhood::VonNeumann)
     }
 
     pub fn diagonal_neighbors<'a, 'b: 'a>(
         &'a self,
         pos: &'b Vec2D,
-    ) -> impl Iterator<Item = (&Vec2D, &T)> + 'a {
-        pos.diagonal_neighbors()
-            .flat_map(|pos| self.get(pos))
-            .map(move |val| (pos, val))
+    ) -> impl Iterator<Item = (Vec2D, &'a T)> + 'a {
+        self.neighbors_in(pos, &Neighborhood::Diagonal)
     }
 
-    pub fn all_neighbors<'a, 'b: 'a>(&'a self, pos: &'b Vec2D) -> impl Iterator<Item = (&Vec2D, &T)> + 'a {
-        pos.all_neighbors()
-            .flat_map(|pos| self.get(pos))
-            .map(move |val| (pos, val))
+    pub fn all_neighbors<'a, 'b: 'a>(
+        &'a self,
+        pos: &'b Vec2D,
+    ) -> impl Iterator<Item = (Vec2D, &'a T)> + 'a {
+        self.neighbors_in(pos, &Neighborhood::Moore)
+    }
+}
+
+impl<T> graphs::WeightedGraph for SparseGrid<T>
+where
+    SparseGrid<T>: WeightedGrid,
+{
+    type Node = Vec2D;
+
+    fn neighbors<'a, 'b: 'a>(
+        &'a self,
+        node: &'b Self::Node,
+    ) -> impl Iterator<Item = (Self::Node, graphs::Cost)> + 'a {
+        assert!(self.contains(node));
+        WeightedGrid::neighbors(self, node)
+            .map(move |neighbor| (neighbor, self.cost(*node, neighbor)))
+    }
+}
+
+impl<T> graphs::UnweightedGraph for SparseGrid<T>
+where
+    SparseGrid<T>: UnweightedGrid,
+{
+    type Node = Vec2D;
+
+    fn neighbors<'a, 'b: 'a>(
+        &'a self,
+        node: &'b Self::Node,
+    ) -> impl Iterator<Item = Self::Node> + 'a {
+        assert!(self.contains(node));
+        UnweightedGrid::neighbors(self, node)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::graphs::{dijkstra, floodfill, Cost};
+
+    impl UnweightedGrid for SparseGrid<char> {
+        fn neighbors<'a, 'b: 'a>(&'a self, node: &'b Vec2D) -> impl Iterator<Item = Vec2D> + 'a {
+            self.orthogonal_neighbors(node).filter(|(_, &c)| c != '#').map(|(pos, _)| pos)
+        }
+    }
+
+    impl WeightedGrid for SparseGrid<usize> {
+        fn neighbors<'a, 'b: 'a>(&'a self, node: &'b Vec2D) -> impl Iterator<Item = Vec2D> + 'a {
+            self.orthogonal_neighbors(node).map(|(pos, _)| pos)
+        }
+
+        fn cost(&self, _from: Vec2D, to: Vec2D) -> Cost {
+            self[to]
+        }
+    }
 
     #[test]
     fn get() {
-        let grid: SparseGrid<_> = [((0, 0), "a"), ((1, 0), "b"), ((0, 1), "c"), ((1, 1), "d")]
-            .into_iter()
-            .collect();
+        let grid: SparseGrid<_> =
+            [((0, 0), "a"), ((1, 0), "b"), ((0, 1), "c"), ((1, 1), "d")].into_iter().collect();
 
         assert_eq!(grid.get((0, 0)), Some(&"a"));
         assert_eq!(grid.get((1, 0)), Some(&"b"));
@@ -94,4 +331,123 @@ mod tests {
         assert_eq!(grid.get((0, 2)), None);
         assert_eq!(grid.get((2, 2)), None);
     }
+
+    #[test]
+    fn neighbors() {
+        let grid: SparseGrid<_> =
+            [((0, 0), 'a'), ((1, 0), 'b'), ((0, 1), 'c'), ((2, 2), 'd')].into_iter().collect();
+
+        assert_eq!(
+            grid.orthogonal_neighbors(&Vec2D::new(0, 0)).collect::<Vec<_>>(),
+            vec![(Vec2D::new(1, 0), &'b'), (Vec2D::new(0, 1), &'c')]
+        );
+        assert_eq!(
+            grid.diagonal_neighbors(&Vec2D::new(1, 1)).collect::<Vec<_>>(),
+            vec![(Vec2D::new(2, 2), &'d'), (Vec2D::new(0, 0), &'a')]
+        );
+        assert_eq!(
+            grid.all_neighbors(&Vec2D::new(1, 1)).map(|(pos, _)| pos).collect::<Vec<_>>(),
+            vec![Vec2D::new(0, 1), Vec2D::new(1, 0), Vec2D::new(2, 2), Vec2D::new(0, 0)]
+        );
+    }
+
+    #[test]
+    fn graphs() {
+        // Unbounded maze that is only defined where it is walkable, or where there's a wall
+        let maze: SparseGrid<_> = [
+            ((0, 0), '.'),
+            ((1, 0), '.'),
+            ((1, -1), '#'),
+            ((2, 0), '#'),
+            ((1, 1), '.'),
+            ((2, 1), '.'),
+            ((3, 1), '.'),
+            ((3, 0), '.'),
+            ((10, 10), '.'),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            floodfill(&maze, (0, 0)),
+            [(0, 0), (1, 0), (1, 1), (2, 1), (3, 1), (3, 0)].into_iter().map(Vec2D::from).collect()
+        );
+        assert_eq!(floodfill(&maze, (10, 10)).len(), 1);
+
+        let costs: SparseGrid<usize> =
+            [((0, 0), 0), ((1, 0), 5), ((0, 1), 1), ((1, 1), 1), ((2, 1), 1), ((2, 0), 1)]
+                .into_iter()
+                .collect();
+        assert_eq!(dijkstra(&costs, [Vec2D::new(0, 0)], |&pos| pos == (2, 0)).distance(), Some(4));
+    }
+
+    #[test]
+    fn row_major_iteration() {
+        let grid: SparseGrid<_> =
+            [((1, 1), 'd'), ((0, 1), 'c'), ((1, 0), 'b'), ((0, 0), 'a')].into_iter().collect();
+        assert_eq!(
+            grid.iter().collect::<Vec<_>>(),
+            vec![
+                (Vec2D::new(0, 0), &'a'),
+                (Vec2D::new(1, 0), &'b'),
+                (Vec2D::new(0, 1), &'c'),
+                (Vec2D::new(1, 1), &'d')
+            ]
+        );
+        assert_eq!(grid.values().collect::<String>(), "abcd");
+        assert_eq!(grid.into_iter().map(|(_, c)| c).rev().collect::<String>(), "dcba");
+    }
+
+    #[test]
+    fn bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+
+        grid.insert((1, 1), 'a');
+        assert_eq!(grid.bounds(), Some(Box2D::new((1, 1), (1, 1))));
+
+        grid.insert((-2, 3), 'b');
+        grid.insert((4, 0), 'c');
+        grid.insert((4, 2), 'd');
+        assert_eq!(grid.bounds(), Some(Box2D::new((-2, 0), (4, 3))));
+
+        grid.remove((-2, 3));
+        assert_eq!(grid.bounds(), Some(Box2D::new((1, 0), (4, 2))));
+
+        // Still one position left with x = 4
+        grid.remove((4, 0));
+        assert_eq!(grid.bounds(), Some(Box2D::new((1, 1), (4, 2))));
+
+        // Overwriting doesn't count twice
+        grid.insert((4, 2), 'e');
+        grid.remove((4, 2));
+        assert_eq!(grid.bounds(), Some(Box2D::new((1, 1), (1, 1))));
+
+        grid.remove((1, 1));
+        assert_eq!(grid.bounds(), None);
+        assert!(grid.is_empty());
+    }
+
+    #[test]
+    fn to_dense_grid() {
+        let grid: SparseGrid<_> = [((-1, 0), 1), ((1, 1), 2)].into_iter().collect();
+        assert_eq!(
+            grid.to_option_grid(),
+            Grid::from([[Some(1), None, None], [None, None, Some(2)]])
+        );
+        assert_eq!(grid.to_grid(0), Grid::from([[1, 0, 0], [0, 0, 2]]));
+        assert_eq!(SparseGrid::<i32>::new().to_grid(0), Grid::new(vec![]));
+    }
+
+    #[test]
+    fn neighbors_in() {
+        let grid: SparseGrid<_> =
+            [((0, 0), "a"), ((1, 0), "b"), ((1, 1), "d")].into_iter().collect();
+
+        assert_eq!(
+            grid.neighbors_in(&Vec2D::new(0, 0), &Neighborhood::Moore).collect::<Vec<_>>(),
+            vec![(Vec2D::new(1, 0), &"b"), (Vec2D::new(1, 1), &"d")]
+        );
+        assert_eq!(grid.neighbors_in(&Vec2D::new(0, 0), &Neighborhood::Diagonal).count(), 1);
+    }
 }